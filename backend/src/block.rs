@@ -0,0 +1,87 @@
+use crate::cube::{Cube, FaceType};
+
+/// The stickers of the piece at the given position, where `x` counts from L to R,
+/// `y` from D to U, and `z` from B to F. The core of the cube has no stickers.
+fn piece_stickers(x: usize, y: usize, z: usize) -> Vec<(FaceType, usize, usize)> {
+    let mut stickers = Vec::new();
+    match x {
+        0 => stickers.push((FaceType::L, 2 - y, z)),
+        2 => stickers.push((FaceType::R, 2 - y, 2 - z)),
+        _ => {}
+    }
+    match y {
+        0 => stickers.push((FaceType::D, 2 - z, x)),
+        2 => stickers.push((FaceType::U, z, x)),
+        _ => {}
+    }
+    match z {
+        0 => stickers.push((FaceType::B, 2 - y, 2 - x)),
+        2 => stickers.push((FaceType::F, 2 - y, x)),
+        _ => {}
+    }
+    stickers
+}
+
+/// The dimensions of the largest box of solved pieces on the cube, from smallest to largest,
+/// such as `(1, 2, 3)` for a Roux first block. Pieces are solved relative to the centres.
+/// Only boxes containing at least one edge or corner count, so a cube with no solved edges
+/// or corners gives `(0, 0, 0)`.
+pub fn largest_solved_block(cube: &Cube<3>) -> (usize, usize, usize) {
+    let mut solved = [[[false; 3]; 3]; 3];
+    let mut is_piece = [[[false; 3]; 3]; 3];
+    for x in 0..3 {
+        for y in 0..3 {
+            for z in 0..3 {
+                let stickers = piece_stickers(x, y, z);
+                solved[x][y][z] = stickers.iter().all(|&(face, row, col)| {
+                    cube.face(face)[(row, col)] == cube.solved_color_at(face, row, col)
+                });
+                is_piece[x][y][z] = stickers.len() > 1;
+            }
+        }
+    }
+
+    let mut best = (0, 0, 0);
+    let ranges = (0..3).flat_map(|start| (start..3).map(move |end| start..=end));
+    for xs in ranges.clone() {
+        for ys in ranges.clone() {
+            for zs in ranges.clone() {
+                let positions = || {
+                    xs.clone().flat_map(|x| {
+                        let zs = zs.clone();
+                        ys.clone()
+                            .flat_map(move |y| zs.clone().map(move |z| (x, y, z)))
+                    })
+                };
+                if positions().all(|(x, y, z)| solved[x][y][z])
+                    && positions().any(|(x, y, z)| is_piece[x][y][z])
+                {
+                    let mut dimensions = [xs.clone(), ys.clone(), zs.clone()]
+                        .map(|range| range.end() - range.start() + 1);
+                    dimensions.sort_unstable();
+                    let [a, b, c] = dimensions;
+                    if a * b * c > best.0 * best.1 * best.2 {
+                        best = (a, b, c);
+                    }
+                }
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocks() {
+        let block = |alg: &str| largest_solved_block(&Cube::new().apply_algorithm(alg).unwrap());
+        assert_eq!(block(""), (3, 3, 3));
+        assert_eq!(block("R"), (2, 3, 3));
+        // The left half of the E slice is the only block left.
+        assert_eq!(block("U D' R"), (1, 2, 3));
+        let (a, b, c) = block("B R2 U2 F R' U' B2 F U R2 U2 L' D' R2 D L R' F' R F2 B2 U D' R L2");
+        assert!(a * b * c <= 2);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{
+    cube::{Cube, Move, MoveSequence},
+    group::{CyclicGroup, Enumerable, GroupAction, InverseSemigroup, Magma, Unital},
+    permute::{CornerCubelet, CubePermutation3, EdgeCubelet},
+};
+
+/// The longest insertion we search for. Interchanges are always a single move.
+const MAX_INSERTION_LENGTH: usize = 3;
+
+/// Checks that exactly three edges or exactly three corners are out of place, and nothing else is.
+fn is_pure_three_cycle(permutation: &CubePermutation3) -> bool {
+    let moved_edges = EdgeCubelet::enumerate()
+        .into_iter()
+        .filter(|&edge| {
+            permutation.edges().act(&(edge, CyclicGroup::identity()))
+                != (edge, CyclicGroup::identity())
+        })
+        .count();
+    let moved_corners = CornerCubelet::enumerate()
+        .into_iter()
+        .filter(|&corner| {
+            permutation
+                .corners()
+                .act(&(corner, CyclicGroup::identity()))
+                != (corner, CyclicGroup::identity())
+        })
+        .count();
+    permutation.centres() == &Unital::identity()
+        && ((moved_edges, moved_corners) == (3, 0) || (moved_edges, moved_corners) == (0, 3))
+}
+
+/// All move sequences of the given length with no two consecutive moves on the same axis,
+/// together with their permutations.
+fn sequences(moves: &[Move], length: usize) -> Vec<(Vec<Move>, CubePermutation3)> {
+    let mut result = vec![(Vec::new(), CubePermutation3::identity())];
+    for _ in 0..length {
+        let mut next = Vec::new();
+        for (seq, permutation) in result {
+            for &mv in moves {
+                if seq.last().map(|last: &Move| last.axis) == Some(mv.axis) {
+                    continue;
+                }
+                let mut seq = seq.clone();
+                seq.push(mv);
+                next.push((seq, CubePermutation3::from_move(mv).op(permutation)));
+            }
+        }
+        result = next;
+    }
+    result
+}
+
+/// If the cube is a pure 3-cycle of edges or corners, searches for a commutator `A B A' B'` that solves it,
+/// returning the two parts `A` and `B`.
+/// One part is always a single move, and the other is at most three moves long.
+/// Shorter commutators are preferred.
+/// Cycles that need a setup move before a commutator applies, such as most last layer 3-cycles, are not found.
+pub fn find_commutator(cube: &Cube<3>) -> Option<(Vec<Move>, Vec<Move>)> {
+    let permutation = CubePermutation3::from_cube(cube)?;
+    if !is_pure_three_cycle(&permutation) {
+        return None;
+    }
+    let target = permutation.inverse();
+
+    let moves = "F F2 F' R R2 R' U U2 U' B B2 B' L L2 L' D D2 D' M M2 M' E E2 E' S S2 S'"
+        .parse::<MoveSequence>()
+        .unwrap()
+        .moves;
+    let single = sequences(&moves, 1);
+    for length in 1..=MAX_INSERTION_LENGTH {
+        let insertions = sequences(&moves, length);
+        for (insertion, p) in &insertions {
+            for (interchange, q) in &single {
+                // Try both [insertion, interchange] and [interchange, insertion].
+                if q.inverse().op(p.inverse()).op(*q).op(*p) == target {
+                    return Some((insertion.clone(), interchange.clone()));
+                }
+                if p.inverse().op(q.inverse()).op(*p).op(*q) == target {
+                    return Some((interchange.clone(), insertion.clone()));
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(cube: Cube<3>, moves: &[Move]) -> Cube<3> {
+        moves.iter().fold(cube, |cube, &mv| cube.perform(mv))
+    }
+
+    fn check_commutator(setup: &str) {
+        let cube = apply(Cube::new(), &setup.parse::<MoveSequence>().unwrap().moves);
+        let (a, b) = find_commutator(&cube).unwrap();
+        let a_inverse = MoveSequence { moves: a.clone() }.inverse().moves;
+        let b_inverse = MoveSequence { moves: b.clone() }.inverse().moves;
+        let solved = [a, b, a_inverse, b_inverse]
+            .iter()
+            .fold(cube, |cube, part| apply(cube, part));
+        assert_eq!(solved, Cube::new());
+    }
+
+    #[test]
+    fn edge_three_cycle() {
+        // Undone by [M', U2].
+        check_commutator("U2 M' U2 M");
+    }
+
+    #[test]
+    fn corner_three_cycle() {
+        // Undone by [D, R U R'].
+        check_commutator("R U R' D R U' R' D'");
+    }
+
+    #[test]
+    fn not_a_three_cycle() {
+        let cube = apply(
+            Cube::new(),
+            &"R U R' U'".parse::<MoveSequence>().unwrap().moves,
+        );
+        assert_eq!(find_commutator(&cube), None);
+    }
+}
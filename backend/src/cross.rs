@@ -0,0 +1,161 @@
+use std::fmt::Display;
+
+use crate::{
+    cube::{Colour, Cube, EdgeType, FaceType},
+    group::Enumerable,
+    permute::edge_facelets,
+};
+
+/// A cross edge that is not yet solved, and where it is now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrossEdge {
+    /// The colours of the edge, with the cross colour first.
+    pub colours: (Colour, Colour),
+    pub position: EdgeType,
+    /// Whether the cross colour is on the position's other sticker, rather than its key sticker.
+    /// The key sticker is the U or D sticker, or for middle layer edges, the F or B sticker.
+    pub flipped: bool,
+}
+
+/// How far through solving the cross of a given colour a cube is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrossStatus {
+    /// The number of cross edges in their home positions, correctly oriented.
+    pub solved: usize,
+    /// The cross edges that are not solved.
+    pub missing: Vec<CrossEdge>,
+}
+
+impl Display for CrossStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/4 cross edges", self.solved)?;
+        for edge in &self.missing {
+            write!(
+                f,
+                ", {:?}-{:?} is {}in {}",
+                edge.colours.0,
+                edge.colours.1,
+                if edge.flipped { "flipped " } else { "" },
+                edge.position
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Works out which edges of the cross of the given colour are solved, and where the others are.
+/// The cross is built around the centre of that colour, wherever it is.
+///
+/// Panics if the cube does not have each cross edge exactly once.
+pub fn cross_status(cube: &Cube<3>, colour: Colour) -> CrossStatus {
+    let centre = |face: FaceType| cube.face(face)[(1, 1)];
+    let cross_face = FaceType::enumerate()
+        .into_iter()
+        .find(|&face| centre(face) == colour)
+        .expect("no centre has the cross colour");
+
+    let mut status = CrossStatus {
+        solved: 0,
+        missing: Vec::new(),
+    };
+    for side in FaceType::enumerate() {
+        if side == cross_face || side.is_opposite(cross_face) {
+            continue;
+        }
+        let colours = (colour, centre(side));
+        let (position, flipped) = EdgeType::enumerate()
+            .into_iter()
+            .find_map(|edge| {
+                let [key, other] =
+                    edge_facelets(edge).map(|(face, row, col)| cube.face(face)[(row, col)]);
+                if (key, other) == colours {
+                    Some((edge, false))
+                } else if (other, key) == colours {
+                    Some((edge, true))
+                } else {
+                    None
+                }
+            })
+            .expect("cross edge is missing from the cube");
+
+        let cross_sticker = edge_facelets(position)[flipped as usize];
+        let home = edge_facelets(position)
+            .iter()
+            .all(|&(face, _, _)| face == cross_face || face == side);
+        if home && cross_sticker.0 == cross_face {
+            status.solved += 1;
+        } else {
+            status.missing.push(CrossEdge {
+                colours,
+                position,
+                flipped,
+            });
+        }
+    }
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cube::FaceType::*;
+
+    use super::*;
+
+    #[test]
+    fn solved_cross() {
+        for colour in FaceType::enumerate().map(FaceType::solved_colour) {
+            let status = cross_status(&Cube::new(), colour);
+            assert_eq!(status.solved, 4);
+            assert!(status.missing.is_empty());
+        }
+        let solved = |alg: &str, colour| {
+            cross_status(&Cube::new().apply_algorithm(alg).unwrap(), colour).solved
+        };
+        // Turning the opposite face does not affect the cross.
+        assert_eq!(solved("U", Colour::Yellow), 4);
+        // The edges are in the cross, but not lined up with their centres.
+        assert_eq!(solved("D", Colour::Yellow), 0);
+        // The cross follows its centre when the whole cube is rotated, here to R.
+        assert_eq!(solved("x y L2", Colour::White), 4);
+        assert_eq!(solved("x y R", Colour::White), 0);
+    }
+
+    #[test]
+    fn partial_cross() {
+        let status = cross_status(&Cube::new().apply_algorithm("R").unwrap(), Colour::White);
+        assert_eq!(status.solved, 3);
+        assert_eq!(
+            status.missing,
+            vec![CrossEdge {
+                colours: (Colour::White, Colour::Red),
+                position: EdgeType::BR,
+                flipped: false,
+            }]
+        );
+
+        let status = cross_status(
+            &Cube::new().apply_algorithm("R2 L2").unwrap(),
+            Colour::Yellow,
+        );
+        assert_eq!(
+            status.to_string(),
+            "2/4 cross edges, Yellow-Red is in UR, Yellow-Orange is in UL"
+        );
+
+        let status = cross_status(&Cube::new().apply_algorithm("F").unwrap(), Colour::White);
+        assert_eq!(
+            status.to_string(),
+            "3/4 cross edges, White-Green is flipped in FR"
+        );
+
+        // Flip the white-green edge in place.
+        let mut cube = Cube::new();
+        cube.face_mut(U)[(2, 1)] = Colour::Green;
+        cube.face_mut(F)[(0, 1)] = Colour::White;
+        let status = cross_status(&cube, Colour::White);
+        assert_eq!(
+            status.to_string(),
+            "3/4 cross edges, White-Green is flipped in UF"
+        );
+    }
+}
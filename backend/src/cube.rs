@@ -1,23 +1,41 @@
-use std::{collections::HashMap, fmt::Display, ops::Index, str::FromStr};
+use rand::Rng;
+use std::{
+    borrow::Borrow,
+    collections::BTreeMap,
+    fmt::Display,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
 use wasm_bindgen::{prelude::*, JsCast};
 
 /// Represents a *valid* (i.e. has all of the required pieces, not necessarily solvable) NxN cube.
 /// Not `Copy` primarily as a lint.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Cube<const N: usize> {
     /// Faces of the cube, ordered F R U B L D.
     faces: [Face<N>; 6],
 }
 
+/// A 3x3x3 cube. Functions that only make sense for this size, such as last layer recognition, take this type.
+///
+/// Cubes of any size from 1x1x1 upwards can be built. A cube with no layers is rejected at compile time
+/// by every constructor of [`Cube`] and [`Face`]; moves that need more layers than the cube has,
+/// such as slice moves on a 1x1x1 cube, are rejected at runtime when they are parsed or performed.
+///
+/// ```compile_fail,E0080
+/// let cube = autocuber::cube::Cube::<0>::new();
+/// ```
+pub type Cube3 = Cube<3>;
+
 /// A face of an NxN cube.
 /// Not `Copy` primarily as a lint.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Face<const N: usize> {
     rows: [[Colour; N]; N],
 }
 
 /// The colour of a face on an NxN cube.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(u8)]
 // Colours are often not constructed directly, but converted into from a face type.
 #[allow(dead_code)]
@@ -42,11 +60,40 @@ impl Colour {
             Colour::Yellow => 'y',
         }
     }
+
+    /// The face that has this colour when the cube is solved.
+    pub fn home_face(self) -> FaceType {
+        match self {
+            Colour::Green => F,
+            Colour::Red => R,
+            Colour::White => U,
+            Colour::Blue => B,
+            Colour::Orange => L,
+            Colour::Yellow => D,
+        }
+    }
+}
+
+/// Colours can be written as their letter or their name, in any case.
+impl FromStr for Colour {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "g" | "green" => Ok(Colour::Green),
+            "r" | "red" => Ok(Colour::Red),
+            "w" | "white" => Ok(Colour::White),
+            "b" | "blue" => Ok(Colour::Blue),
+            "o" | "orange" => Ok(Colour::Orange),
+            "y" | "yellow" => Ok(Colour::Yellow),
+            _ => Err(()),
+        }
+    }
 }
 
 /// A face on a cube.
 /// Represented in Singmaster notation.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(u8)]
 pub enum FaceType {
     F,
@@ -87,6 +134,36 @@ impl Display for FaceType {
     }
 }
 
+impl FaceType {
+    /// The colour of this face when the cube is solved.
+    pub fn solved_colour(self) -> Colour {
+        match self {
+            F => Colour::Green,
+            R => Colour::Red,
+            U => Colour::White,
+            B => Colour::Blue,
+            L => Colour::Orange,
+            D => Colour::Yellow,
+        }
+    }
+
+    /// The face on the other side of the cube.
+    pub fn opposite(self) -> FaceType {
+        match self {
+            F => B,
+            R => L,
+            U => D,
+            B => F,
+            L => R,
+            D => U,
+        }
+    }
+
+    pub fn is_opposite(self, other: FaceType) -> bool {
+        self.opposite() == other
+    }
+}
+
 impl Enumerable for FaceType {
     const N: usize = 6;
 
@@ -293,6 +370,17 @@ pub enum Axis {
 }
 use Axis::*;
 
+impl Axis {
+    /// The faces at either end of the axis: the face of its first layer, then the opposite face.
+    pub fn faces(self) -> (FaceType, FaceType) {
+        match self {
+            FB => (F, B),
+            RL => (R, L),
+            UD => (U, D),
+        }
+    }
+}
+
 impl FromStr for Axis {
     type Err = ();
 
@@ -306,15 +394,14 @@ impl FromStr for Axis {
     }
 }
 
-/// These impls are safe since colour and face type are `repr(u8)` and have the same possible discriminants.
 impl From<FaceType> for Colour {
     fn from(face: FaceType) -> Self {
-        unsafe { std::mem::transmute(face) }
+        face.solved_colour()
     }
 }
 impl From<Colour> for FaceType {
     fn from(colour: Colour) -> Self {
-        unsafe { std::mem::transmute(colour) }
+        colour.home_face()
     }
 }
 
@@ -374,7 +461,7 @@ pub fn inverse_wasm(rot: RotationType) -> RotationType {
 }
 
 #[wasm_bindgen]
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct Move {
     pub axis: Axis,
     #[wasm_bindgen(js_name = rotationType)]
@@ -390,32 +477,125 @@ pub struct Move {
     pub end_depth: usize,
 }
 
+/// A slice move, turning the middle layer between two opposite faces.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SliceType {
+    /// The slice between L and R, turning in the same direction as L.
+    M,
+    /// The slice between U and D, turning in the same direction as D.
+    E,
+    /// The slice between F and B, turning in the same direction as F.
+    S,
+}
+
+/// A way of writing moves. The dialects differ in how they write wide moves.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Dialect {
+    /// The notation used by the WCA, in which wide moves are written like `Rw`.
+    #[default]
+    Wca,
+    /// SiGN notation, in which wide moves are written in lowercase like `r`.
+    Sign,
+}
+
+/// Parsing a move accepts wide moves written in any dialect.
 impl FromStr for Move {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        const N: usize = 3;
-        let mut chars = s.chars();
-        let face_char = chars.next().ok_or(())?;
+        Move::parse_sized(s, 3).map_err(|_| ())
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_string_in(3, Dialect::default()))
+    }
+}
+
+impl Move {
+    /// The slice move on a cube of size `n`, which turns only its middle layer.
+    /// Returns None if `n` is even or 1, since then there is no middle layer separate from the outer layers.
+    pub fn slice(slice: SliceType, rotation_type: RotationType, n: usize) -> Option<Self> {
+        if n < 3 || n % 2 != 1 {
+            return None;
+        }
+        // M and E turn the same way as the faces at the far end of their axis.
+        let (axis, rotation_type) = match slice {
+            SliceType::M => (RL, rotation_type.inverse()),
+            SliceType::E => (UD, rotation_type.inverse()),
+            SliceType::S => (FB, rotation_type),
+        };
+        Some(Self::new(axis, rotation_type, n / 2, n / 2 + 1))
+    }
+
+    /// The wide move on a cube of size `n` that turns the given face together with the next `depth - 1` layers
+    /// as one block, such as `Rw` for a depth of 2, or `3Rw` for a depth of 3.
+    /// Returns None unless the block has at least one layer, and leaves at least one layer unturned.
+    pub fn wide(
+        face: FaceType,
+        rotation_type: RotationType,
+        depth: usize,
+        n: usize,
+    ) -> Option<Self> {
+        if depth == 0 || depth >= n {
+            return None;
+        }
+        Some(match face {
+            F => Self::new(FB, rotation_type, 0, depth),
+            R => Self::new(RL, rotation_type, 0, depth),
+            U => Self::new(UD, rotation_type, 0, depth),
+            B => Self::new(FB, rotation_type.inverse(), n - depth, n),
+            L => Self::new(RL, rotation_type.inverse(), n - depth, n),
+            D => Self::new(UD, rotation_type.inverse(), n - depth, n),
+        })
+    }
+
+    /// Parses a single move on a 3x3x3 cube, explaining what is wrong with it if it cannot be parsed.
+    pub fn parse(s: &str) -> Result<Self, ParseMoveError> {
+        Self::parse_sized(s, 3).map_err(|error| ParseMoveError {
+            token: s.to_string(),
+            position: 0,
+            index: 0,
+            error,
+        })
+    }
+
+    /// Parses a move on a cube of size `n`.
+    /// As well as the usual notation, a layer number may be given before the face:
+    /// `3R` turns only the third layer from the right, and `3Rw` (or `3r`) turns the three outermost layers.
+    pub fn parse_sized(s: &str, n: usize) -> Result<Self, MoveError> {
+        let digits = s.chars().take_while(char::is_ascii_digit).count();
+        let layers = if digits == 0 {
+            None
+        } else {
+            Some(
+                s[..digits]
+                    .parse()
+                    .map_err(|_| MoveError::InvalidNotation)?,
+            )
+        };
+        let mut chars = s[digits..].chars();
+        let face_char = chars.next().ok_or(MoveError::InvalidNotation)?;
         let turn_direction = match face_char {
             'M' => 'L',
             'E' => 'D',
             'S' => 'F',
+            'x' => 'R',
+            'y' => 'U',
+            'z' => 'F',
             x => x,
         };
-        let face: FaceType = turn_direction.to_uppercase().collect::<String>().parse()?;
-        let mut end_depth = if face_char.is_lowercase() { 2 } else { 1 };
-        let mut start_depth = match face_char {
-            'M' | 'E' | 'S' => {
-                end_depth = 2;
-                1
-            }
-            _ => 0,
-        };
+        let face: FaceType = turn_direction
+            .to_uppercase()
+            .collect::<String>()
+            .parse()
+            .map_err(|_| MoveError::UnknownFace(face_char))?;
+        let mut wide = face_char.is_lowercase() && !matches!(face_char, 'x' | 'y' | 'z');
         let mut rotation_type = RotationType::Normal;
         for modification in chars {
             match modification {
-                'w' => end_depth = 2,
+                'w' => wide = true,
                 '2' => rotation_type = RotationType::Double,
                 '\'' => {
                     // Sometimes, algorithms have things like U2', but we don't care
@@ -424,35 +604,52 @@ impl FromStr for Move {
                         rotation_type = RotationType::Inverse
                     }
                 }
-                _ => return Err(()),
+                _ => return Err(MoveError::InvalidModifier(modification)),
             }
         }
-        let axis = match face {
-            F => FB,
-            R => RL,
-            U => UD,
-            B => {
-                rotation_type = rotation_type.inverse();
-                let d = start_depth;
-                start_depth = N - end_depth;
-                end_depth = N - d;
-                FB
-            }
-            L => {
-                rotation_type = rotation_type.inverse();
-                let d = start_depth;
-                start_depth = N - end_depth;
-                end_depth = N - d;
-                RL
+
+        let (mut start_depth, mut end_depth) = match face_char {
+            // Slice moves turn the middle layer, so they only exist on odd cubes with more than one layer.
+            'M' | 'E' | 'S' if layers.is_none() && !wide && n % 2 == 1 && n > 1 => {
+                (n / 2, n / 2 + 1)
             }
-            D => {
-                rotation_type = rotation_type.inverse();
-                let d = start_depth;
-                start_depth = N - end_depth;
-                end_depth = N - d;
-                UD
+            // Whole cube rotations turn every slice.
+            'x' | 'y' | 'z' if layers.is_none() && !wide => (0, n),
+            'M' | 'E' | 'S' | 'x' | 'y' | 'z' => return Err(MoveError::InvalidNotation),
+            _ if wide => {
+                let layers = layers.unwrap_or(2);
+                if layers == 0 || layers >= n {
+                    return Err(MoveError::TooWide {
+                        layers,
+                        cube_size: n,
+                    });
+                }
+                (0, layers)
             }
+            _ => match layers {
+                Some(0) => return Err(MoveError::InvalidNotation),
+                Some(layer) => (layer - 1, layer),
+                None => (0, 1),
+            },
+        };
+        let axis = match face {
+            F | B => FB,
+            R | L => RL,
+            U | D => UD,
         };
+        if end_depth > n {
+            // Layers are counted from the named face, so B, L and D moves are reported as written.
+            return Err(MoveError::InvalidDepth {
+                mv: Self::new(axis, rotation_type, start_depth, end_depth),
+                cube_size: n,
+            });
+        }
+        if matches!(face, B | L | D) {
+            rotation_type = rotation_type.inverse();
+            let d = start_depth;
+            start_depth = n - end_depth;
+            end_depth = n - d;
+        }
         Ok(Self {
             axis,
             rotation_type,
@@ -460,38 +657,135 @@ impl FromStr for Move {
             end_depth,
         })
     }
-}
 
-impl Display for Move {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match (self.start_depth, self.end_depth) {
-            (0, 1) => match self.axis {
-                FB => write!(f, "F{}", self.rotation_type),
-                RL => write!(f, "R{}", self.rotation_type),
-                UD => write!(f, "U{}", self.rotation_type),
-            },
-            (1, 2) => match self.axis {
-                FB => write!(f, "S{}", self.rotation_type),
-                RL => write!(f, "M{}", self.rotation_type.inverse()),
-                UD => write!(f, "E{}", self.rotation_type.inverse()),
-            },
-            (2, 3) => match self.axis {
-                FB => write!(f, "B{}", self.rotation_type.inverse()),
-                RL => write!(f, "L{}", self.rotation_type.inverse()),
-                UD => write!(f, "D{}", self.rotation_type.inverse()),
-            },
-            _ => {
-                // Fallback if we don't know how else to display the move:
-                write!(
-                    f,
-                    "{:?}{}-{}{}",
-                    self.axis, self.start_depth, self.end_depth, self.rotation_type
-                )
+    /// Writes the move in the notation for a cube of size `n`, so that `Move::parse_sized` reads it back.
+    /// Inner layers are numbered from the nearer face, as in `2R` or `3Lw'`.
+    /// A block of inner layers that does not include a face has no such notation,
+    /// and is written in the same fallback form that `Display` uses for moves it cannot name.
+    pub fn to_string_sized(self, n: usize) -> String {
+        self.to_string_in(n, Dialect::default())
+    }
+
+    /// Parses a move on a cube of size `n`, only accepting wide moves written in the given dialect.
+    pub fn parse_in(s: &str, n: usize, dialect: Dialect) -> Result<Self, MoveError> {
+        let face_char = s
+            .chars()
+            .find(|c| !c.is_ascii_digit())
+            .ok_or(MoveError::InvalidNotation)?;
+        let wide_in_dialect = match dialect {
+            Dialect::Wca => face_char.is_uppercase() || matches!(face_char, 'x' | 'y' | 'z'),
+            Dialect::Sign => !s.contains('w'),
+        };
+        if wide_in_dialect {
+            Move::parse_sized(s, n)
+        } else {
+            Err(MoveError::InvalidNotation)
+        }
+    }
+
+    /// Writes the move in the given dialect, in the notation for a cube of size `n`, as `to_string_sized` does.
+    pub fn to_string_in(self, n: usize, dialect: Dialect) -> String {
+        let (near, far) = match self.axis {
+            FB => ('F', 'B'),
+            RL => ('R', 'L'),
+            UD => ('U', 'D'),
+        };
+        let block = |face: char, depth: usize| match (dialect, depth) {
+            (_, 1) => face.to_string(),
+            (Dialect::Wca, 2) => format!("{}w", face),
+            (Dialect::Wca, _) => format!("{}{}w", depth, face),
+            (Dialect::Sign, 2) => face.to_lowercase().to_string(),
+            (Dialect::Sign, _) => format!("{}{}", depth, face.to_lowercase()),
+        };
+        let (start, end) = (self.start_depth, self.end_depth);
+        if (start, end) == (0, n) {
+            let rotation = match self.axis {
+                FB => 'z',
+                RL => 'x',
+                UD => 'y',
+            };
+            format!("{}{}", rotation, self.rotation_type)
+        } else if end > n {
+            format!("{:?}{}-{}{}", self.axis, start, end, self.rotation_type)
+        } else if n % 2 == 1 && n > 1 && (start, end) == (n / 2, n / 2 + 1) {
+            match self.axis {
+                FB => format!("S{}", self.rotation_type),
+                RL => format!("M{}", self.rotation_type.inverse()),
+                UD => format!("E{}", self.rotation_type.inverse()),
             }
+        } else if start == 0 {
+            format!("{}{}", block(near, end), self.rotation_type)
+        } else if end == n {
+            format!("{}{}", block(far, n - start), self.rotation_type.inverse())
+        } else if end == start + 1 && end <= n - start {
+            format!("{}{}{}", end, near, self.rotation_type)
+        } else if end == start + 1 {
+            format!("{}{}{}", n - start, far, self.rotation_type.inverse())
+        } else {
+            format!("{:?}{}-{}{}", self.axis, start, end, self.rotation_type)
+        }
+    }
+}
+
+impl Move {
+    /// The face this move is counted from: the face of the first layer of its axis if the move turns that layer,
+    /// and the opposite face otherwise. For example, `R` and `Rw` give R, but `L`, `M` and `2R` give L.
+    pub fn face(self) -> FaceType {
+        let (near, far) = self.axis.faces();
+        if self.start_depth == 0 {
+            near
+        } else {
+            far
+        }
+    }
+
+    /// The faces this move turns on a cube of size `n`, as counted by the half turn metric.
+    /// A block of layers at the edge of the cube turns the face there, a block inside the cube
+    /// turns the two faces around it, and a whole cube rotation turns no faces.
+    pub fn faces_turned(self, n: usize) -> Vec<FaceType> {
+        let (near, far) = self.axis.faces();
+        match (self.start_depth == 0, self.end_depth == n) {
+            (true, true) => Vec::new(),
+            (true, false) => vec![near],
+            (false, true) => vec![far],
+            (false, false) => vec![near, far],
+        }
+    }
+
+    /// The key by which moves are sorted: by face, then rotation, then depth.
+    /// Moves that do not turn the first layer of their axis are sorted with the opposite face,
+    /// and their rotation is as seen from that face, so B, L and D sort just like F, R and U.
+    fn sort_key(&self) -> (FaceType, RotationType, usize, usize) {
+        (
+            self.face(),
+            self.face_rotation(),
+            self.start_depth,
+            self.end_depth,
+        )
+    }
+
+    /// The direction of this move as seen from [`Move::face`], so that `R`, `L` and `M'` all turn clockwise.
+    pub fn face_rotation(self) -> RotationType {
+        if self.start_depth == 0 {
+            self.rotation_type
+        } else {
+            self.rotation_type.inverse()
         }
     }
 }
 
+impl PartialOrd for Move {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Move {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.sort_key().cmp(&other.sort_key())
+    }
+}
+
 #[wasm_bindgen]
 impl Move {
     pub fn new(
@@ -508,6 +802,7 @@ impl Move {
         }
     }
 
+    /// The move that undoes this one, turning the same layers the other way.
     pub fn inverse(self) -> Self {
         Self {
             rotation_type: self.rotation_type.inverse(),
@@ -515,11 +810,59 @@ impl Move {
         }
     }
 
+    /// The reflection of this move in the plane between the L and R faces, on a cube of size `n`.
+    /// For example, `R` becomes `L'`, and `U` becomes `U'`.
+    pub fn mirrored(self, n: usize) -> Self {
+        match self.axis {
+            RL => Self {
+                start_depth: n - self.end_depth,
+                end_depth: n - self.start_depth,
+                ..self
+            },
+            FB | UD => self.inverse(),
+        }
+    }
+
     pub fn clone_move(&self) -> Self {
         *self
     }
 }
 
+/// Every turn of a single outer face of a 3x3x3 cube, in the order U, D, F, B, R, L,
+/// each as a clockwise, double, then anticlockwise turn.
+pub const fn all_basic_moves() -> [Move; 18] {
+    const fn layer(axis: Axis, start_depth: usize, rotation_type: RotationType) -> Move {
+        Move {
+            axis,
+            rotation_type,
+            start_depth,
+            end_depth: start_depth + 1,
+        }
+    }
+    use RotationType::*;
+    // A clockwise turn of the far face is an anticlockwise turn about its axis.
+    [
+        layer(UD, 0, Normal),
+        layer(UD, 0, Double),
+        layer(UD, 0, Inverse),
+        layer(UD, 2, Inverse),
+        layer(UD, 2, Double),
+        layer(UD, 2, Normal),
+        layer(FB, 0, Normal),
+        layer(FB, 0, Double),
+        layer(FB, 0, Inverse),
+        layer(FB, 2, Inverse),
+        layer(FB, 2, Double),
+        layer(FB, 2, Normal),
+        layer(RL, 0, Normal),
+        layer(RL, 0, Double),
+        layer(RL, 0, Inverse),
+        layer(RL, 2, Inverse),
+        layer(RL, 2, Double),
+        layer(RL, 2, Normal),
+    ]
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct MoveSequence {
     pub moves: Vec<Move>,
@@ -539,7 +882,7 @@ impl Semigroup for MoveSequence {}
 impl InverseSemigroup for MoveSequence {
     fn inverse(&self) -> Self {
         Self {
-            moves: self.moves.iter().rev().map(|mv| mv.inverse()).collect(),
+            moves: invert_sequence(&self.moves),
         }
     }
 }
@@ -558,7 +901,9 @@ impl MoveSequence {
 
         let mut process_axis = |current_axis: Axis, current_axis_moves: Vec<Move>| {
             // Canonicalise the list of current axis moves, since they all must commute.
-            let mut turns_by_slice = HashMap::<usize, i32>::new();
+            // The slices are kept in order, so that a block of slices turning together (such as a
+            // whole cube rotation) becomes a single move.
+            let mut turns_by_slice = BTreeMap::<usize, i32>::new();
             for mv in current_axis_moves {
                 for slice in mv.start_depth..mv.end_depth {
                     *turns_by_slice.entry(slice).or_default() += mv.rotation_type.rotations();
@@ -638,31 +983,126 @@ impl From<MoveSequence> for MoveSequenceConv {
 }
 
 impl FromStr for MoveSequence {
-    type Err = ();
+    type Err = ParseMoveError;
 
+    /// Parses moves on a 3x3x3 cube separated by whitespace, as `parse_algorithm` does.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut result = Self { moves: Vec::new() };
-        for value in s.split(' ') {
-            result.moves.push(value.parse()?);
-        }
-        Ok(result)
+        parse_algorithm(s).map(|moves| Self { moves })
+    }
+}
+
+/// A move in an algorithm that could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseMoveError {
+    /// The move as it was written.
+    pub token: String,
+    /// The byte offset of the move in the algorithm.
+    pub position: usize,
+    /// The index of the move in the algorithm, counting from zero.
+    pub index: usize,
+    /// Why the move could not be parsed.
+    pub error: MoveError,
+}
+
+impl Display for ParseMoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid move '{}' at position {}: {}",
+            self.token, self.position, self.error
+        )
+    }
+}
+
+/// Parses an algorithm written as moves separated by whitespace.
+/// Rather than stopping at the first move that cannot be parsed, every such move is reported.
+pub fn validate_algorithm(input: &str) -> Result<Vec<Move>, Vec<ParseMoveError>> {
+    let (moves, errors): (Vec<_>, Vec<_>) = parse_tokens(input).partition(Result::is_ok);
+    if errors.is_empty() {
+        Ok(moves.into_iter().map(Result::unwrap).collect())
+    } else {
+        Err(errors.into_iter().map(Result::unwrap_err).collect())
     }
 }
 
+/// Parses an algorithm written as moves separated by whitespace, stopping at the first move that cannot be parsed.
+/// An empty algorithm has no moves.
+pub fn parse_algorithm(input: &str) -> Result<Vec<Move>, ParseMoveError> {
+    parse_tokens(input).collect()
+}
+
+/// The moves that undo a sequence of moves: the inverse of each move, in reverse order.
+pub fn invert_sequence(moves: &[Move]) -> Vec<Move> {
+    moves.iter().rev().map(|mv| mv.inverse()).collect()
+}
+
+/// The algorithm that undoes an algorithm, so `R U F` becomes `F' U' R'`.
+/// This is `invert_sequence`, named to go with `parse_algorithm`.
+pub fn invert_algorithm(moves: &[Move]) -> Vec<Move> {
+    invert_sequence(moves)
+}
+
+/// Parses each whitespace-separated move of an algorithm on a 3x3x3 cube.
+fn parse_tokens(input: &str) -> impl Iterator<Item = Result<Move, ParseMoveError>> + '_ {
+    parse_tokens_with(input, |token| Move::parse_sized(token, 3))
+}
+
+/// Parses each whitespace-separated move of an algorithm with the given parser,
+/// recording where in the algorithm each move that cannot be parsed was written.
+fn parse_tokens_with<'a>(
+    input: &'a str,
+    parse: impl Fn(&str) -> Result<Move, MoveError> + 'a,
+) -> impl Iterator<Item = Result<Move, ParseMoveError>> + 'a {
+    input
+        .split_whitespace()
+        .enumerate()
+        .map(move |(index, token)| {
+            parse(token).map_err(|error| ParseMoveError {
+                token: token.to_string(),
+                position: token.as_ptr() as usize - input.as_ptr() as usize,
+                index,
+                error,
+            })
+        })
+}
+
 impl Display for MoveSequence {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for (i, mv) in self.moves.iter().enumerate() {
-            if i != 0 {
-                write!(f, " ")?;
-            }
-            write!(f, "{}", mv)?;
-        }
-        Ok(())
+        write!(f, "{}", self.to_string_in(3, Dialect::default()))
+    }
+}
+
+impl MoveSequence {
+    /// Parses a whitespace-separated sequence of moves on a cube of size `n`,
+    /// only accepting wide moves written in the given dialect.
+    pub fn parse_in(s: &str, n: usize, dialect: Dialect) -> Result<Self, ParseMoveError> {
+        let moves = parse_tokens_with(s, |token| Move::parse_in(token, n, dialect))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { moves })
+    }
+
+    /// Writes the sequence of moves in the given dialect, in the notation for a cube of size `n`.
+    pub fn to_string_in(&self, n: usize, dialect: Dialect) -> String {
+        self.moves
+            .iter()
+            .map(|mv| mv.to_string_in(n, dialect))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl<const N: usize> Default for Cube<N> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl<const N: usize> Cube<N> {
+    /// Using this fails to compile if the cube has no layers.
+    const HAS_LAYERS: () = Face::<N>::HAS_LAYERS;
+
     pub fn new() -> Self {
+        let () = Self::HAS_LAYERS;
         Self {
             faces: [
                 Face::new(F),
@@ -679,7 +1119,248 @@ impl<const N: usize> Cube<N> {
         &self.faces[ty as usize]
     }
 
+    /// Gives direct access to the stickers of a face.
+    /// This can be used to construct cubes that are not reachable from the solved state.
+    pub fn face_mut(&mut self, ty: FaceType) -> &mut Face<N> {
+        &mut self.faces[ty as usize]
+    }
+
+    /// The reflection of this cube in the plane between the L and R faces.
+    /// The L and R colours are swapped too, so the reflection of a solved cube is solved,
+    /// and performing the mirrored moves on the mirrored cube gives the mirror of the result.
+    pub fn mirrored(&self) -> Self {
+        let mut result = Self::new();
+        for face in FaceType::enumerate() {
+            let source = match face {
+                R => L,
+                L => R,
+                _ => face,
+            };
+            for i in 0..N {
+                for j in 0..N {
+                    result.face_mut(face)[(i, j)] = match self.face(source)[(i, N - 1 - j)] {
+                        Colour::Red => Colour::Orange,
+                        Colour::Orange => Colour::Red,
+                        colour => colour,
+                    };
+                }
+            }
+        }
+        result
+    }
+
+    /// Whether each sticker is the colour it would be on a solved cube, indexed by face, row, and column.
+    pub fn solved_mask(&self) -> [[[bool; N]; N]; 6] {
+        let mut mask = [[[false; N]; N]; 6];
+        for face in FaceType::enumerate() {
+            for (i, row) in mask[face as usize].iter_mut().enumerate() {
+                for (j, sticker) in row.iter_mut().enumerate() {
+                    *sticker = self.face(face)[(i, j)] == face.solved_colour();
+                }
+            }
+        }
+        mask
+    }
+
+    /// The fraction of stickers that are in their solved position, between 0 and 1.
+    /// On odd cubes, the fixed centres are not counted.
+    pub fn solved_fraction(&self) -> f64 {
+        let mask = self.solved_mask();
+        let mut solved = 0;
+        let mut total = 0;
+        for face in mask {
+            for (i, row) in face.iter().enumerate() {
+                for (j, &sticker) in row.iter().enumerate() {
+                    if N % 2 == 1 && i == N / 2 && j == N / 2 {
+                        continue;
+                    }
+                    total += 1;
+                    if sticker {
+                        solved += 1;
+                    }
+                }
+            }
+        }
+        solved as f64 / total as f64
+    }
+
+    /// The colour the given sticker has once the cube is solved, which is the colour of its face's centre.
+    /// This respects the cube's current orientation, so it works however the cube is held.
+    /// Even cubes have no fixed centre, so the face's own colour is used instead.
+    pub fn solved_color_at(&self, face: FaceType, row: usize, col: usize) -> Colour {
+        assert!(
+            row < N && col < N,
+            "sticker ({}, {}) is outside the face",
+            row,
+            col
+        );
+        if N % 2 == 1 {
+            self.face(face)[(N / 2, N / 2)]
+        } else {
+            face.solved_colour()
+        }
+    }
+
+    /// Whether the layer at the given face is solved: every sticker on the face, and the strip of
+    /// stickers on each adjacent face that belongs to the same layer, has its solved colour.
+    /// A face of a single colour is not enough, since the pieces on it may still be in the wrong places.
+    pub fn is_face_solved(&self, face: FaceType) -> bool {
+        self.stickers()
+            .filter(|&(f, i, j)| in_layer(face, f, i, j, N))
+            .all(|(f, i, j)| self.face(f)[(i, j)] == self.solved_color_at(f, i, j))
+    }
+
+    /// Whether every face is a single colour, in whatever orientation the cube is held.
+    pub fn is_solved(&self) -> bool {
+        self.faces.iter().all(|face| {
+            let colour = face.rows[0][0];
+            face.rows.iter().flatten().all(|&c| c == colour)
+        })
+    }
+
+    /// Rotates the whole cube so that the given face is on top,
+    /// returning the rotated cube and the rotations used, of which there is at most one.
+    pub fn orient_face_up(self, face: FaceType) -> (Self, Vec<Move>) {
+        let rotation = match face {
+            U => None,
+            D => Some((RL, RotationType::Double)),
+            F => Some((RL, RotationType::Normal)),
+            B => Some((RL, RotationType::Inverse)),
+            R => Some((FB, RotationType::Inverse)),
+            L => Some((FB, RotationType::Normal)),
+        };
+        let moves = rotation
+            .map(|(axis, rotation_type)| Move::new(axis, rotation_type, 0, N))
+            .into_iter()
+            .collect::<Vec<_>>();
+        let cube = moves.iter().fold(self, |cube, &mv| cube.perform(mv));
+        (cube, moves)
+    }
+
+    /// Whether two cubes match everywhere except the layer at the given face, which may differ.
+    /// For instance, ignoring U compares the first two layers, whatever the last layer looks like.
+    pub fn equal_ignoring_face(&self, other: &Cube<N>, ignore: FaceType) -> bool {
+        self.stickers()
+            .filter(|&(f, i, j)| !in_layer(ignore, f, i, j, N))
+            .all(|(f, i, j)| self.face(f)[(i, j)] == other.face(f)[(i, j)])
+    }
+
+    /// The positions of every sticker on the cube.
+    fn stickers(&self) -> impl Iterator<Item = (FaceType, usize, usize)> {
+        FaceType::enumerate()
+            .into_iter()
+            .flat_map(|face| (0..N).flat_map(move |i| (0..N).map(move |j| (face, i, j))))
+    }
+
+    /// Rotates the stickers of a single face, as seen looking at that face, leaving every other sticker in place.
+    /// This is not a real move, but is useful for building test cases, such as rotated centres on a supercube.
+    pub fn rotate_face_only(&mut self, face: FaceType, rotation: RotationType) {
+        let rotated = match rotation {
+            RotationType::Normal => self.face(face).rotate_cw(),
+            RotationType::Double => self.face(face).rotate_double(),
+            RotationType::Inverse => self.face(face).rotate_ccw(),
+        };
+        *self.face_mut(face) = rotated;
+    }
+
+    /// The states the cube passes through while performing the moves, starting with the current state.
+    pub fn trace(&self, moves: &[Move]) -> Vec<Self> {
+        let mut states = vec![self.clone()];
+        for &mv in moves {
+            states.push(states.last().unwrap().clone().perform(mv));
+        }
+        states
+    }
+
+    /// Performs a move, panicking if the move does not fit on this cube.
     pub fn perform(self, mv: Move) -> Self {
+        match self.try_perform(mv) {
+            Ok(cube) => cube,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Performs each move in order, panicking if any move does not fit on this cube.
+    pub fn perform_all(self, moves: impl IntoIterator<Item = Move>) -> Self {
+        moves.into_iter().fold(self, Self::perform)
+    }
+
+    /// Performs each move of a sequence in order, panicking if any move does not fit on this cube.
+    /// This is `perform_all` for a slice of moves, such as a parsed scramble.
+    pub fn apply_sequence(self, moves: &[Move]) -> Self {
+        self.perform_all(moves.iter().copied())
+    }
+
+    /// As `apply_sequence`, but changes the cube in place.
+    pub fn apply_sequence_in_place(&mut self, moves: &[Move]) {
+        *self = std::mem::take(self).apply_sequence(moves);
+    }
+
+    /// Generates a random scramble of `length` outer layer moves, in the style of WCA scrambles.
+    /// No two moves in a row turn the same face, and no three moves in a row turn faces on the same axis,
+    /// so there is nothing like `R R'` or `R L R`.
+    pub fn scramble(rng: &mut impl Rng, length: usize) -> Vec<Move> {
+        let faces = FaceType::enumerate();
+        let rotation_types = [
+            RotationType::Normal,
+            RotationType::Double,
+            RotationType::Inverse,
+        ];
+        let mut turned = Vec::<FaceType>::with_capacity(length);
+        while turned.len() < length {
+            let face = faces[rng.gen_range(0..faces.len())];
+            let same_axis = |other: &FaceType| *other == face || *other == face.opposite();
+            match turned.as_slice() {
+                [.., last] if *last == face => {}
+                [.., first, second] if same_axis(first) && same_axis(second) => {}
+                _ => turned.push(face),
+            }
+        }
+        turned
+            .into_iter()
+            .map(|face| {
+                let rotation_type = rotation_types[rng.gen_range(0..rotation_types.len())];
+                let (axis, near) = match face {
+                    F => (FB, true),
+                    R => (RL, true),
+                    U => (UD, true),
+                    B => (FB, false),
+                    L => (RL, false),
+                    D => (UD, false),
+                };
+                if near {
+                    Move::new(axis, rotation_type, 0, 1)
+                } else {
+                    Move::new(axis, rotation_type.inverse(), N - 1, N)
+                }
+            })
+            .collect()
+    }
+
+    /// Performs a move, or returns an error if the move does not fit on this cube.
+    pub fn try_perform(self, mv: Move) -> Result<Self, MoveError> {
+        if mv.start_depth >= mv.end_depth || mv.end_depth > N {
+            return Err(MoveError::InvalidDepth { mv, cube_size: N });
+        }
+        Ok(self.perform_unchecked(mv))
+    }
+
+    /// Performs each move of an algorithm written in notation for a cube of this size,
+    /// such as `3Rw U 3Rw'` on a 5x5x5 cube.
+    /// Returns an error naming the first move that cannot be parsed or does not fit on this cube.
+    pub fn apply_algorithm(self, alg: &str) -> Result<Self, AlgorithmError> {
+        alg.split_whitespace().try_fold(self, |cube, token| {
+            Move::parse_sized(token, N)
+                .and_then(|mv| cube.try_perform(mv))
+                .map_err(|error| AlgorithmError {
+                    token: token.to_string(),
+                    error,
+                })
+        })
+    }
+
+    /// Performs a move whose depths are known to be valid.
+    fn perform_unchecked(self, mv: Move) -> Self {
         // Heavily optimised move-performing logic.
         macro_rules! face {
             ( $start_depth:ident, $end_depth:ident, ($($x:tt)*) ) => {
@@ -764,8 +1445,9 @@ impl<const N: usize> Cube<N> {
                     // (the left part of R's face is copied from the bottom part of U's face)
                     (R Left U Bottom)
                     (U Bottom L Right)
-                    // "B is clockwise, but only if the back face is modified" (back face signalled by the `b` character)
-                    (B b cw)
+                    // "B is anticlockwise, but only if the back face is modified" (back face signalled by the `b` character)
+                    // A clockwise turn seen from the front is an anticlockwise turn seen from the back.
+                    (B b ccw)
                     (L Right D Top)
                     (D Top R Left)
                 ),
@@ -791,7 +1473,7 @@ impl<const N: usize> Cube<N> {
                     (F ccw)
                     (R Left D Top)
                     (U Bottom R Left)
-                    (B b ccw)
+                    (B b cw)
                     (L Right U Bottom)
                     (D Top L Right)
                 ),
@@ -806,7 +1488,7 @@ impl<const N: usize> Cube<N> {
                     (R cw)
                     (U Right F Right)
                     (B Left U Right)
-                    (L b cw)
+                    (L b ccw)
                     (D Right B Left)
                 ),
                 Move {
@@ -832,7 +1514,7 @@ impl<const N: usize> Cube<N> {
                     (R ccw)
                     (U Right B Left)
                     (B Left D Right)
-                    (L b ccw)
+                    (L b cw)
                     (D Right F Right)
                 ),
                 // UD turns
@@ -847,7 +1529,7 @@ impl<const N: usize> Cube<N> {
                     (U cw)
                     (B Top L Top)
                     (L Top F Top)
-                    (D b cw)
+                    (D b ccw)
                 ),
                 Move {
                     axis: UD,
@@ -873,57 +1555,699 @@ impl<const N: usize> Cube<N> {
                     (U ccw)
                     (B Top R Top)
                     (L Top B Top)
-                    (D b ccw)
+                    (D b cw)
                 ),
             },
         }
     }
 }
 
-impl<const N: usize> Display for Cube<N> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // Write the U face.
-        for i in 0..N {
-            // Write each row.
-            for _ in 0..N {
-                // Add a gap at the start for the L face.
-                write!(f, "  ")?;
-            }
-            // Display the row.
-            for j in 0..N {
-                write!(f, "{} ", self.face(U)[(i, j)].letter())?;
-            }
-            writeln!(f)?;
-        }
+/// Lazily performs a stream of moves on a cube, yielding the state after each move.
+/// Only the current state is kept, so this suits very long sequences better than `Cube::trace`.
+pub trait MoveIterExt: Iterator + Sized {
+    fn scan_cube<const N: usize>(self, start: Cube<N>) -> ScanCube<Self, N>;
+}
 
-        // Write the L, F, R, B faces.
-        for i in 0..N {
-            for face in [L, F, R, B] {
-                for j in 0..N {
-                    write!(f, "{} ", self.face(face)[(i, j)].letter())?;
-                }
-            }
-            writeln!(f)?;
+impl<I> MoveIterExt for I
+where
+    I: Iterator,
+    I::Item: Borrow<Move>,
+{
+    fn scan_cube<const N: usize>(self, start: Cube<N>) -> ScanCube<Self, N> {
+        ScanCube {
+            moves: self,
+            cube: start,
         }
+    }
+}
 
-        // Write the D face.
-        for i in 0..N {
-            // Write each row.
-            for _ in 0..N {
-                // Add a gap at the start for the L face.
-                write!(f, "  ")?;
-            }
-            // Display the row.
-            for j in 0..N {
-                write!(f, "{} ", self.face(D)[(i, j)].letter())?;
+/// The iterator returned by `MoveIterExt::scan_cube`.
+pub struct ScanCube<I, const N: usize> {
+    moves: I,
+    cube: Cube<N>,
+}
+
+impl<I, const N: usize> Iterator for ScanCube<I, N>
+where
+    I: Iterator,
+    I::Item: Borrow<Move>,
+{
+    type Item = Cube<N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mv = *self.moves.next()?.borrow();
+        self.cube = self.cube.clone().perform(mv);
+        Some(self.cube.clone())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.moves.size_hint()
+    }
+}
+
+impl<const N: usize> Cube<N> {
+    /// Renders the cube as a net, with the U face above the L, F, R, B faces, and the D face below.
+    /// The `cell` function gives the text to display for the sticker at a given face, row, and column.
+    /// Every cell is padded to the width of the widest cell, so the faces stay aligned.
+    pub fn render_net(&self, cell: impl Fn(FaceType, usize, usize) -> String) -> String {
+        let cells = FaceType::enumerate().map(|face| {
+            (0..N)
+                .map(|i| (0..N).map(|j| cell(face, i, j)).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        });
+        let width = cells
+            .iter()
+            .flatten()
+            .flatten()
+            .map(|s| s.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut result = String::new();
+        let mut write_row = |faces: &[FaceType], i: usize, indent: bool| {
+            if indent {
+                // Add a gap at the start for the L face.
+                result.push_str(&" ".repeat(N * (width + 1)));
+            }
+            for &face in faces {
+                for text in &cells[face as usize][i] {
+                    result.push_str(&format!("{:width$} ", text));
+                }
+            }
+            result.push('\n');
+        };
+
+        for i in 0..N {
+            write_row(&[U], i, true);
+        }
+        for i in 0..N {
+            write_row(&[L, F, R, B], i, false);
+        }
+        for i in 0..N {
+            write_row(&[D], i, true);
+        }
+
+        result
+    }
+}
+
+impl<const N: usize> Display for Cube<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", AsciiRenderer.render(self))
+    }
+}
+
+/// An error produced when a move cannot be performed on a cube.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveError {
+    /// The move turns no layers, or layers deeper than the cube has.
+    InvalidDepth { mv: Move, cube_size: usize },
+    /// The move is not written in a notation we understand.
+    InvalidNotation,
+    /// The move does not name a face, slice, or rotation.
+    UnknownFace(char),
+    /// The face is followed by something other than `w`, `2`, or `'`.
+    InvalidModifier(char),
+    /// A wide move turns every layer of the cube, or more.
+    TooWide { layers: usize, cube_size: usize },
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveError::InvalidDepth { mv, cube_size } => write!(
+                f,
+                "cannot turn layers {} to {} of a cube of size {}",
+                mv.start_depth, mv.end_depth, cube_size
+            ),
+            MoveError::InvalidNotation => write!(f, "not a valid move"),
+            MoveError::UnknownFace(c) => write!(f, "unknown face '{}'", c),
+            MoveError::InvalidModifier(c) => write!(f, "unexpected '{}' after the face", c),
+            MoveError::TooWide { layers, cube_size } => write!(
+                f,
+                "a wide move cannot turn {} layers of a cube of size {}",
+                layers, cube_size
+            ),
+        }
+    }
+}
+
+/// An error produced when a move of an algorithm cannot be performed on a cube.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlgorithmError {
+    /// The move as it was written in the algorithm.
+    pub token: String,
+    pub error: MoveError,
+}
+
+impl Display for AlgorithmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cannot apply {}: {}", self.token, self.error)
+    }
+}
+
+/// An error produced when a cube given by the user cannot be used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CubeError {
+    /// The cube could not be read.
+    Facelets(FaceletError),
+    /// The stickers could not belong to a real cube.
+    Invalid(CubeValidationError),
+    /// The centres cannot be rotated into the standard colour scheme.
+    InvalidCentres,
+    /// The cube is made of real pieces, but cannot be solved.
+    Unsolvable(crate::solvability::SolvabilityReport),
+    /// The solver could not find a solution.
+    NoSolution,
+}
+
+impl Display for CubeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CubeError::Facelets(e) => write!(f, "{}", e),
+            CubeError::Invalid(e) => write!(f, "{}", e),
+            CubeError::InvalidCentres => {
+                write!(f, "the centres are not in the standard colour scheme")
+            }
+            CubeError::Unsolvable(report) => write!(f, "the cube cannot be solved: {}", report),
+            CubeError::NoSolution => write!(f, "no solution was found"),
+        }
+    }
+}
+
+impl From<FaceletError> for CubeError {
+    fn from(e: FaceletError) -> Self {
+        CubeError::Facelets(e)
+    }
+}
+
+impl From<CubeValidationError> for CubeError {
+    fn from(e: CubeValidationError) -> Self {
+        CubeError::Invalid(e)
+    }
+}
+
+/// Repeatedly applies the algorithm until the cube is solved,
+/// returning the number of repetitions needed, or None if it takes more than `max_reps`.
+/// Starting from a solved cube, this gives the order of the algorithm.
+pub fn apply_until_solved<const N: usize>(
+    mut cube: Cube<N>,
+    alg: &[Move],
+    max_reps: usize,
+) -> Option<usize> {
+    let solved = Cube::new();
+    for reps in 1..=max_reps {
+        for &mv in alg {
+            cube = cube.perform(mv);
+        }
+        if cube == solved {
+            return Some(reps);
+        }
+    }
+    None
+}
+
+/// A reason why a cube's stickers could not belong to a real cube.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CubeValidationError {
+    /// A colour appears on the wrong number of stickers.
+    WrongColourCount {
+        colour: Colour,
+        found: usize,
+        expected: usize,
+    },
+    /// On an odd cube, two centres are the same colour.
+    DuplicateCenter,
+    /// On an odd cube, the centres are six different colours, but no whole cube rotation
+    /// puts every centre on the face of its colour.
+    CentresNotStandard,
+}
+
+impl Display for CubeValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CubeValidationError::WrongColourCount {
+                colour,
+                found,
+                expected,
+            } => write!(
+                f,
+                "expected {} {:?} stickers but found {}",
+                expected, colour, found
+            ),
+            CubeValidationError::DuplicateCenter => {
+                write!(f, "two of the centres are the same colour")
+            }
+            CubeValidationError::CentresNotStandard => write!(
+                f,
+                "the centres are not in the standard colour scheme, however the cube is held"
+            ),
+        }
+    }
+}
+
+impl<const N: usize> Cube<N> {
+    /// The number of stickers of each colour, indexed by colour.
+    pub fn colour_counts(&self) -> [usize; 6] {
+        let mut counts = [0; 6];
+        for &sticker in self
+            .faces
+            .iter()
+            .flat_map(|face| face.rows.iter().flatten())
+        {
+            counts[sticker as usize] += 1;
+        }
+        counts
+    }
+
+    /// Checks that the cube is valid, in the sense of the type's documentation.
+    ///
+    /// # Panics
+    /// Panics if a colour appears on the wrong number of stickers, naming the colour and how many it has,
+    /// or if the cube has odd size and its centres are not in the standard colour scheme.
+    pub fn validate(&self) {
+        if let Err(error) = self.try_validate() {
+            panic!("invalid cube: {}", error);
+        }
+    }
+
+    /// Checks that each colour appears on exactly N*N stickers, and on odd cubes,
+    /// that the centres are in the standard colour scheme when the cube is held the right way round.
+    /// Unlike `validate`, this reports what is wrong instead of panicking.
+    pub fn try_validate(&self) -> Result<(), CubeValidationError> {
+        let counts = self.colour_counts();
+        for colour in FaceType::enumerate().map(FaceType::solved_colour) {
+            let found = counts[colour as usize];
+            if found != N * N {
+                return Err(CubeValidationError::WrongColourCount {
+                    colour,
+                    found,
+                    expected: N * N,
+                });
+            }
+        }
+        if N % 2 == 1 {
+            let centres = FaceType::enumerate().map(|face| self.face(face)[(N / 2, N / 2)]);
+            if centres
+                .iter()
+                .enumerate()
+                .any(|(i, centre)| centres[..i].contains(centre))
+            {
+                return Err(CubeValidationError::DuplicateCenter);
+            }
+            if !colour_schemes().contains(&centres) {
+                return Err(CubeValidationError::CentresNotStandard);
+            }
+        }
+        Ok(())
+    }
+
+    /// The colour of most of the centre stickers of a face, that is, the stickers not on its edge.
+    /// On a cube whose centres are solved, this is the colour of the face.
+    /// Returns None if no colour is more common than every other, or the face has no centre stickers.
+    pub fn center_color_of(&self, face: FaceType) -> Option<Colour> {
+        let inner = if N == 1 { 0..1 } else { 1..N - 1 };
+        let mut counts = [0; 6];
+        for i in inner.clone() {
+            for j in inner.clone() {
+                counts[self.face(face)[(i, j)] as usize] += 1;
+            }
+        }
+        let most = *counts.iter().max().unwrap();
+        let mut colours = FaceType::enumerate()
+            .map(FaceType::solved_colour)
+            .into_iter()
+            .filter(|&colour| counts[colour as usize] == most);
+        match (colours.next(), colours.next()) {
+            (Some(colour), None) if most > 0 => Some(colour),
+            _ => None,
+        }
+    }
+
+    /// Works out the colour of each face, indexed by face, for example when some centres were scanned unclearly.
+    /// The faces whose centre colour is known from `center_color_of` must fit the standard colour scheme
+    /// in some orientation, so for instance the face opposite white must be yellow.
+    /// Returns None unless exactly one orientation fits.
+    pub fn resolve_centers(&self) -> Option<[Colour; 6]> {
+        let known = FaceType::enumerate().map(|face| self.center_color_of(face));
+        let mut fitting = colour_schemes().into_iter().filter(|scheme| {
+            scheme
+                .iter()
+                .zip(known)
+                .all(|(&colour, known)| known.is_none_or(|known| known == colour))
+        });
+        match (fitting.next(), fitting.next()) {
+            (Some(scheme), None) => Some(scheme),
+            _ => None,
+        }
+    }
+
+    /// Starts from a solved cube and recolours the given stickers, checking that the result is valid.
+    pub fn solved_with_edits(
+        edits: &[(FaceType, usize, usize, Colour)],
+    ) -> Result<Self, CubeValidationError> {
+        let cube = Self::solved_with_edits_unchecked(edits);
+        cube.try_validate()?;
+        Ok(cube)
+    }
+
+    /// Starts from a solved cube and recolours the given stickers.
+    /// The result may not be a valid cube, which is useful for testing invalid states.
+    pub fn solved_with_edits_unchecked(edits: &[(FaceType, usize, usize, Colour)]) -> Self {
+        let mut cube = Self::new();
+        for &(face, row, col, colour) in edits {
+            cube.face_mut(face)[(row, col)] = colour;
+        }
+        cube
+    }
+}
+
+/// The colour of each face, indexed by face, in each of the 24 orientations of a cube in the standard colour scheme.
+fn colour_schemes() -> Vec<[Colour; 6]> {
+    let rotation = |axis, rotation_type| Move::new(axis, rotation_type, 0, 1);
+    // First bring each face to the top, then turn the cube about the vertical axis.
+    let to_top = [
+        None,
+        Some(rotation(RL, RotationType::Normal)),
+        Some(rotation(RL, RotationType::Double)),
+        Some(rotation(RL, RotationType::Inverse)),
+        Some(rotation(FB, RotationType::Normal)),
+        Some(rotation(FB, RotationType::Inverse)),
+    ];
+    let mut schemes = Vec::new();
+    for first in to_top {
+        let mut cube = first.map_or_else(Cube::<1>::new, |mv| Cube::new().perform(mv));
+        for _ in 0..4 {
+            schemes.push(FaceType::enumerate().map(|face| cube.face(face)[(0, 0)]));
+            cube = cube.perform(rotation(UD, RotationType::Normal));
+        }
+    }
+    schemes
+}
+
+/// The order in which faces appear in a facelet string.
+const FACELET_ORDER: [FaceType; 6] = [U, R, F, D, L, B];
+
+/// The position of a sticker in a facelet string for an NxN cube, between 0 and 6N^2.
+/// Faces are ordered U R F D L B, and each face is read row by row.
+pub fn facelet_index(face: FaceType, row: usize, col: usize, n: usize) -> usize {
+    let face_index = FACELET_ORDER.iter().position(|&f| f == face).unwrap();
+    face_index * n * n + row * n + col
+}
+
+/// The face, row, and column of the sticker at a position in a facelet string for an NxN cube.
+/// This is the inverse of `facelet_index`.
+pub fn facelet_coords(index: usize, n: usize) -> (FaceType, usize, usize) {
+    (
+        FACELET_ORDER[index / (n * n)],
+        index % (n * n) / n,
+        index % n,
+    )
+}
+
+impl<const N: usize> Cube<N> {
+    /// Rearranges the stickers by a permutation of the positions in a facelet string, in the same form as
+    /// `Move::permutation_array`: afterwards, the sticker at position `i` is the one that was at position `perm[i]`.
+    /// The cube is left unchanged if `perm` is not a permutation of the 6N^2 positions.
+    pub fn apply_facelet_permutation(&mut self, perm: &[usize]) -> Result<(), CubeError> {
+        if perm.len() != 6 * N * N {
+            return Err(FaceletError::WrongPermutationLength {
+                expected: 6 * N * N,
+                found: perm.len(),
+            }
+            .into());
+        }
+        let mut used = vec![false; perm.len()];
+        for &source in perm {
+            if source >= perm.len() || std::mem::replace(&mut used[source], true) {
+                return Err(FaceletError::NotAPermutation { source }.into());
             }
-            writeln!(f)?;
         }
 
+        let original = self.clone();
+        for (target, &source) in perm.iter().enumerate() {
+            let (face, i, j) = facelet_coords(target, N);
+            let (source_face, source_i, source_j) = facelet_coords(source, N);
+            self.face_mut(face)[(i, j)] = original.face(source_face)[(source_i, source_j)];
+        }
         Ok(())
     }
 }
 
+impl Move {
+    /// The effect of this move on a facelet string for an NxN cube, as a list of length 6N^2.
+    /// After the move, the sticker at position `i` is the one that was at position `result[i]`.
+    pub fn permutation_array(&self, n: usize) -> Vec<usize> {
+        assert!(
+            self.end_depth <= n,
+            "move {} does not fit on a {}x{}x{} cube",
+            self,
+            n,
+            n,
+            n
+        );
+        // Stickers are placed in space with coordinates doubled, so that the centre of the cube is at the origin.
+        // Each coordinate runs from L to R, D to U, and B to F.
+        // The sticker sits on the piece at `position`, on the side facing `normal`.
+        let m = n as isize - 1;
+        let to_space = |index: usize| {
+            let (face, row, col) = facelet_coords(index, n);
+            let (row, col) = (row as isize, col as isize);
+            let (piece, normal) = match face {
+                F => ([col, m - row, m], [0, 0, 1]),
+                B => ([m - col, m - row, 0], [0, 0, -1]),
+                U => ([col, m, row], [0, 1, 0]),
+                D => ([col, 0, m - row], [0, -1, 0]),
+                R => ([m, m - row, m - col], [1, 0, 0]),
+                L => ([0, m - row, col], [-1, 0, 0]),
+            };
+            (piece.map(|x| 2 * x - m), normal)
+        };
+        let from_space = |position: [isize; 3], normal: [isize; 3]| {
+            let [x, y, z] = position.map(|x| ((x + m) / 2) as usize);
+            let m = m as usize;
+            let (face, row, col) = match normal {
+                [0, 0, 1] => (F, m - y, x),
+                [0, 0, -1] => (B, m - y, m - x),
+                [0, 1, 0] => (U, z, x),
+                [0, -1, 0] => (D, m - z, x),
+                [1, 0, 0] => (R, m - y, m - z),
+                _ => (L, m - y, z),
+            };
+            facelet_index(face, row, col, n)
+        };
+
+        // The coordinate along the axis of the move, and a clockwise quarter turn of the near face,
+        // as seen when looking at that face.
+        let axis = match self.axis {
+            FB => 2,
+            RL => 0,
+            UD => 1,
+        };
+        let quarter_turn = |[x, y, z]: [isize; 3]| match self.axis {
+            FB => [y, -x, z],
+            RL => [x, z, -y],
+            UD => [-z, y, x],
+        };
+        let quarter_turns = self.rotation_type.rotations().rem_euclid(4);
+
+        let mut result = (0..6 * n * n).collect::<Vec<_>>();
+        for index in 0..6 * n * n {
+            let (mut position, mut normal) = to_space(index);
+            let depth = ((m - position[axis]) / 2) as usize;
+            if depth < self.start_depth || depth >= self.end_depth {
+                continue;
+            }
+            for _ in 0..quarter_turns {
+                position = quarter_turn(position);
+                normal = quarter_turn(normal);
+            }
+            result[from_space(position, normal)] = index;
+        }
+        result
+    }
+}
+
+/// An error produced when reading a facelet string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaceletError {
+    WrongLength {
+        expected: usize,
+        found: usize,
+    },
+    InvalidCharacter {
+        index: usize,
+        character: char,
+    },
+    /// The input was not a JSON object whose values are arrays of rows of strings.
+    #[cfg(feature = "json")]
+    InvalidJson,
+    MissingFace {
+        face: FaceType,
+    },
+    WrongFaceSize {
+        face: FaceType,
+        expected: usize,
+    },
+    InvalidColour {
+        face: FaceType,
+        row: usize,
+        col: usize,
+    },
+    /// A permutation of the facelets had the wrong length.
+    WrongPermutationLength {
+        expected: usize,
+        found: usize,
+    },
+    /// A permutation of the facelets took a facelet from this position more than once, or from outside the cube.
+    NotAPermutation {
+        source: usize,
+    },
+}
+
+impl Display for FaceletError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FaceletError::WrongLength { expected, found } => {
+                write!(f, "expected {} facelets but found {}", expected, found)
+            }
+            FaceletError::InvalidCharacter { index, character } => {
+                write!(f, "invalid facelet '{}' at index {}", character, index)
+            }
+            #[cfg(feature = "json")]
+            FaceletError::InvalidJson => {
+                write!(f, "expected a JSON object mapping faces to rows of colours")
+            }
+            FaceletError::MissingFace { face } => write!(f, "face {} is missing", face),
+            FaceletError::WrongFaceSize { face, expected } => write!(
+                f,
+                "face {} must have {} rows of {} colours",
+                face, expected, expected
+            ),
+            FaceletError::InvalidColour { face, row, col } => write!(
+                f,
+                "invalid colour at row {} column {} of face {}",
+                row, col, face
+            ),
+            FaceletError::WrongPermutationLength { expected, found } => write!(
+                f,
+                "expected a permutation of {} facelets but found {}",
+                expected, found
+            ),
+            FaceletError::NotAPermutation { source } => write!(
+                f,
+                "facelet {} is not used exactly once in the permutation",
+                source
+            ),
+        }
+    }
+}
+
+impl<const N: usize> Cube<N> {
+    /// Reads a cube from a facelet string, in the format used by Kociemba's solver.
+    /// The faces are given in the order U R F D L B, each read row by row as laid out on the net,
+    /// and each sticker is written as the face whose centre is that colour.
+    pub fn from_facelets(s: &str) -> Result<Self, FaceletError> {
+        let () = Self::HAS_LAYERS;
+        let chars = s.chars().collect::<Vec<_>>();
+        if chars.len() != 6 * N * N {
+            return Err(FaceletError::WrongLength {
+                expected: 6 * N * N,
+                found: chars.len(),
+            });
+        }
+
+        let mut cube = Self::new();
+        for (index, &character) in chars.iter().enumerate() {
+            let face: FaceType = character
+                .to_string()
+                .parse()
+                .map_err(|()| FaceletError::InvalidCharacter { index, character })?;
+            let (facelet_face, i, j) = facelet_coords(index, N);
+            cube.face_mut(facelet_face)[(i, j)] = face.solved_colour();
+        }
+        Ok(cube)
+    }
+
+    /// Writes the cube as a facelet string, in the format read by `from_facelets`.
+    pub fn to_facelets(&self) -> String {
+        (0..6 * N * N)
+            .map(|index| {
+                let (face, i, j) = facelet_coords(index, N);
+                self.face(face)[(i, j)].home_face().to_string()
+            })
+            .collect()
+    }
+
+    /// Reads a cube from a JSON object such as `{ "U": [["w", "w", "w"], ...], "F": ..., ... }`,
+    /// which gives the rows of each face, top to bottom, as laid out on the net.
+    /// Colours may be written as their letter or their name.
+    /// The cube must have the right number of stickers of each colour.
+    #[cfg(feature = "json")]
+    pub fn from_face_json(json: &str) -> Result<Self, CubeError> {
+        let value = serde_json::from_str::<serde_json::Value>(json)
+            .map_err(|_| FaceletError::InvalidJson)?;
+        let object = value.as_object().ok_or(FaceletError::InvalidJson)?;
+
+        let mut cube = Self::new();
+        for face in FaceType::enumerate() {
+            let rows = object
+                .get(&face.to_string())
+                .ok_or(FaceletError::MissingFace { face })?
+                .as_array()
+                .ok_or(FaceletError::InvalidJson)?;
+            let wrong_size = FaceletError::WrongFaceSize { face, expected: N };
+            if rows.len() != N {
+                return Err(wrong_size.into());
+            }
+            for (row, colours) in rows.iter().enumerate() {
+                let colours = colours.as_array().ok_or(FaceletError::InvalidJson)?;
+                if colours.len() != N {
+                    return Err(wrong_size.into());
+                }
+                for (col, colour) in colours.iter().enumerate() {
+                    cube.face_mut(face)[(row, col)] = colour
+                        .as_str()
+                        .and_then(|colour| colour.parse().ok())
+                        .ok_or(FaceletError::InvalidColour { face, row, col })?;
+                }
+            }
+        }
+        cube.try_validate()?;
+        Ok(cube)
+    }
+}
+
+impl Cube<4> {
+    /// The 3x3x3 cube that this cube behaves as, if its centres are solved and its edges are paired.
+    /// Returns None if the cube is not yet reduced.
+    pub fn reduce_to_3x3(&self) -> Option<Cube<3>> {
+        let mut cube = Cube::<3>::new();
+        for face in FaceType::enumerate() {
+            let stickers = self.face(face);
+            let reduced = [(1, 1), (1, 2), (2, 1), (2, 2)]
+                .iter()
+                .all(|&position| stickers[position] == stickers[(1, 1)])
+                && stickers[(0, 1)] == stickers[(0, 2)]
+                && stickers[(3, 1)] == stickers[(3, 2)]
+                && stickers[(1, 0)] == stickers[(2, 0)]
+                && stickers[(1, 3)] == stickers[(2, 3)];
+            if !reduced {
+                return None;
+            }
+            // The outer rows and columns map to the outer rows and columns of the 3x3x3 cube,
+            // and the inner two map to its middle row and column.
+            let source = [0, 1, 3];
+            for i in 0..3 {
+                for j in 0..3 {
+                    cube.face_mut(face)[(i, j)] = stickers[(source[i], source[j])];
+                }
+            }
+        }
+        Some(cube)
+    }
+}
+
 #[derive(Clone, Copy)]
 enum FaceSegment {
     Top,
@@ -933,16 +2257,83 @@ enum FaceSegment {
 }
 use FaceSegment::*;
 
+impl FaceSegment {
+    /// Whether the sticker is in the outermost row or column of this segment on an NxN face.
+    fn contains(self, row: usize, col: usize, n: usize) -> bool {
+        match self {
+            Top => row == 0,
+            Right => col == n - 1,
+            Bottom => row == n - 1,
+            Left => col == 0,
+        }
+    }
+}
+
+/// Whether a sticker on an NxN cube belongs to the outer layer at the given face.
+fn in_layer(layer: FaceType, face: FaceType, row: usize, col: usize, n: usize) -> bool {
+    face == layer
+        || adjacent_segments(layer)
+            .iter()
+            .any(|&(adjacent, segment)| adjacent == face && segment.contains(row, col, n))
+}
+
+/// The segments of the four faces adjacent to the given face that touch it.
+fn adjacent_segments(face: FaceType) -> [(FaceType, FaceSegment); 4] {
+    match face {
+        FaceType::F => [
+            (FaceType::U, Bottom),
+            (FaceType::R, Left),
+            (FaceType::D, Top),
+            (FaceType::L, Right),
+        ],
+        FaceType::R => [
+            (FaceType::U, Right),
+            (FaceType::B, Left),
+            (FaceType::D, Right),
+            (FaceType::F, Right),
+        ],
+        FaceType::U => [
+            (FaceType::F, Top),
+            (FaceType::R, Top),
+            (FaceType::B, Top),
+            (FaceType::L, Top),
+        ],
+        FaceType::B => [
+            (FaceType::U, Top),
+            (FaceType::L, Left),
+            (FaceType::D, Bottom),
+            (FaceType::R, Right),
+        ],
+        FaceType::L => [
+            (FaceType::U, Left),
+            (FaceType::F, Left),
+            (FaceType::D, Left),
+            (FaceType::B, Right),
+        ],
+        FaceType::D => [
+            (FaceType::F, Bottom),
+            (FaceType::R, Bottom),
+            (FaceType::B, Bottom),
+            (FaceType::L, Bottom),
+        ],
+    }
+}
+
 use crate::group::{CyclicGroup, Enumerable, InverseSemigroup, Magma, Semigroup};
+use crate::render::{AsciiRenderer, Renderer};
 
 // The range is there as an optimisation for the compiler, since we
 // know the size of each array at compile time. It also helps unify
 // code style across each of the different functions.
 #[allow(clippy::needless_range_loop)]
 impl<const N: usize> Face<N> {
+    /// Using this fails to compile if the face has no rows.
+    pub(crate) const HAS_LAYERS: () = assert!(N >= 1, "a cube must have at least one layer");
+
     pub fn new(ty: FaceType) -> Self {
+        let () = Self::HAS_LAYERS;
         Self {
-            rows: [[ty.into(); N]; N],
+            rows: [[ty.solved_colour(); N]; N],
         }
     }
 
@@ -1070,3 +2461,1405 @@ impl<const N: usize> Index<(usize, usize)> for Face<N> {
         &self.rows[row][col]
     }
 }
+
+impl<const N: usize> IndexMut<(usize, usize)> for Face<N> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Self::Output {
+        &mut self.rows[row][col]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solved_colours() {
+        for face in FaceType::enumerate() {
+            assert_eq!(face.solved_colour().home_face(), face);
+            assert_eq!(Colour::from(face), face.solved_colour());
+        }
+        assert_eq!(U.solved_colour(), Colour::White);
+        assert_eq!(Colour::Green.home_face(), F);
+    }
+
+    #[test]
+    fn opposite_faces() {
+        for (a, b) in [(F, B), (R, L), (U, D)] {
+            assert_eq!(a.opposite(), b);
+            assert_eq!(b.opposite(), a);
+            assert!(a.is_opposite(b) && b.is_opposite(a));
+        }
+        for face in FaceType::enumerate() {
+            assert_ne!(face.opposite(), face);
+            assert!(!face.is_opposite(face));
+            assert_eq!(
+                FaceType::enumerate()
+                    .iter()
+                    .filter(|&&other| face.is_opposite(other))
+                    .count(),
+                1
+            );
+        }
+    }
+
+    #[test]
+    fn canonicalise_rotations() {
+        let scramble = "R U2 F' L D B2 M E' S"
+            .parse::<MoveSequence>()
+            .unwrap()
+            .moves
+            .into_iter()
+            .fold(Cube::<3>::new(), |cube, mv| cube.perform(mv));
+        for (input, expected) in [
+            ("x x", "x2"),
+            ("y y y", "y'"),
+            ("z' z'", "z2"),
+            ("x2 x", "x'"),
+            ("x x x x", ""),
+            ("y y R", "y2 R"),
+        ] {
+            let input = input.parse::<MoveSequence>().unwrap();
+            let canonical = input.clone().canonicalise();
+            assert_eq!(canonical.to_string(), expected);
+
+            let perform = |seq: MoveSequence| {
+                seq.moves
+                    .into_iter()
+                    .fold(scramble.clone(), |cube, mv| cube.perform(mv))
+            };
+            assert_eq!(perform(input), perform(canonical));
+        }
+    }
+
+    #[test]
+    fn display_wide_moves() {
+        let seq = "r l' u2 d f' b".parse::<MoveSequence>().unwrap();
+        assert_eq!(seq.to_string(), "Rw Lw' Uw2 Dw Fw' Bw");
+        assert_eq!(seq.to_string().parse::<MoveSequence>(), Ok(seq));
+    }
+
+    #[test]
+    fn dialects() {
+        assert_eq!(
+            Move::parse_in("Rw", 3, Dialect::Wca),
+            Move::parse_in("r", 3, Dialect::Sign)
+        );
+        assert_eq!(
+            Move::parse_in("r", 3, Dialect::Wca),
+            Err(MoveError::InvalidNotation)
+        );
+        assert_eq!(
+            Move::parse_in("Rw", 3, Dialect::Sign),
+            Err(MoveError::InvalidNotation)
+        );
+        assert_eq!(
+            Move::parse_in("3r", 5, Dialect::Wca),
+            Err(MoveError::InvalidNotation)
+        );
+        assert!(Move::parse_in("x", 3, Dialect::Wca).is_ok());
+
+        for (n, dialect, alg) in [
+            (3, Dialect::Wca, "Rw U Rw' U' Lw2 Fw Dw' Bw2 R M x"),
+            (3, Dialect::Sign, "r U r' U' l2 f d' b2 R M x"),
+            (4, Dialect::Wca, "Rw 2R' 3Lw2 Bw x' 2U"),
+            (4, Dialect::Sign, "r 2R' 3l2 b x' 2U"),
+            (5, Dialect::Wca, "3Rw M 3Dw' Fw 2L"),
+            (5, Dialect::Sign, "3r M 3d' f 2L"),
+        ] {
+            let seq = MoveSequence::parse_in(alg, n, dialect).unwrap();
+            assert_eq!(seq.to_string_in(n, dialect), alg);
+        }
+        assert_eq!(
+            MoveSequence::parse_in("Rw U Rw' U' Lw2 Fw Dw' Bw2 R M x", 3, Dialect::Wca),
+            MoveSequence::parse_in("r U r' U' l2 f d' b2 R M x", 3, Dialect::Sign)
+        );
+        // Moves may be separated by any whitespace.
+        assert_eq!(
+            MoveSequence::parse_in("  r U\tr'\n  U' ", 3, Dialect::Sign),
+            MoveSequence::parse_in("r U r' U'", 3, Dialect::Sign)
+        );
+        assert_eq!(
+            MoveSequence::parse_in("", 3, Dialect::Wca),
+            Ok(MoveSequence { moves: Vec::new() })
+        );
+        let error = MoveSequence::parse_in("r U  Rw", 3, Dialect::Sign).unwrap_err();
+        assert_eq!((error.token.as_str(), error.position, error.index), ("Rw", 5, 2));
+    }
+
+    #[test]
+    fn try_perform() {
+        let r = "R".parse::<Move>().unwrap();
+        assert_eq!(Cube::<3>::new().try_perform(r), Ok(Cube::new().perform(r)));
+        // Turning the fourth layer of a 3x3x3 cube.
+        let deep = Move::new(Axis::RL, RotationType::Normal, 3, 4);
+        assert_eq!(
+            Cube::<3>::new().try_perform(deep),
+            Err(MoveError::InvalidDepth {
+                mv: deep,
+                cube_size: 3
+            })
+        );
+        assert!(Cube::<4>::new().try_perform(deep).is_ok());
+        let empty = Move::new(Axis::RL, RotationType::Normal, 1, 1);
+        assert!(Cube::<3>::new().try_perform(empty).is_err());
+    }
+
+    #[test]
+    fn invert_sequence() {
+        let moves = super::parse_algorithm("R U2 F' Rw M' x' S E2").unwrap();
+        let inverse = super::invert_sequence(&moves);
+        assert_eq!(
+            inverse,
+            super::parse_algorithm("E2 S' x M Rw' F U2 R'").unwrap()
+        );
+        let cube = Cube::<3>::new().perform_all(moves);
+        assert_ne!(cube, Cube::new());
+        assert_eq!(cube.perform_all(inverse), Cube::new());
+        assert!(super::invert_sequence(&[]).is_empty());
+
+        use rand::{rngs::SmallRng, SeedableRng};
+        let mut rng = SmallRng::seed_from_u64(3);
+        for length in [1, 5, 20, 40] {
+            let alg = Cube::<4>::scramble(&mut rng, length);
+            let start = Cube::<4>::new().apply_algorithm("Rw U' 2F").unwrap();
+            let inverse = super::invert_algorithm(&alg);
+            assert_eq!(inverse, super::invert_sequence(&alg));
+            assert_eq!(start.clone().perform_all(alg).perform_all(inverse), start);
+        }
+    }
+
+    #[test]
+    fn scramble() {
+        use rand::{rngs::SmallRng, SeedableRng};
+
+        let scramble = Cube::<3>::scramble(&mut SmallRng::seed_from_u64(1), 25);
+        assert_eq!(scramble.len(), 25);
+        assert_eq!(
+            scramble,
+            Cube::<3>::scramble(&mut SmallRng::seed_from_u64(1), 25)
+        );
+        let faces = scramble
+            .iter()
+            .map(|mv| (mv.axis, mv.start_depth))
+            .collect::<Vec<_>>();
+        for pair in faces.windows(2) {
+            assert_ne!(pair[0], pair[1]);
+        }
+        for triple in faces.windows(3) {
+            assert!(!(triple[0].0 == triple[1].0 && triple[1].0 == triple[2].0));
+        }
+        assert!(scramble
+            .iter()
+            .all(|mv| mv.start_depth == 0 || mv.end_depth == 3));
+        assert!(!Cube::<3>::new().perform_all(scramble).is_solved());
+
+        let scramble = Cube::<5>::scramble(&mut SmallRng::seed_from_u64(2), 60);
+        assert!(scramble
+            .iter()
+            .all(|mv| mv.end_depth - mv.start_depth == 1
+                && (mv.start_depth == 0 || mv.end_depth == 5)));
+    }
+
+    #[test]
+    fn hash() {
+        let scrambled = || Cube::<3>::new().apply_algorithm("R U2 F' L D B2").unwrap();
+        let mut states = std::collections::HashSet::new();
+        states.insert(scrambled());
+        states.insert(scrambled());
+        assert_eq!(states.len(), 1);
+        states.insert(Cube::new());
+        states.insert(scrambled().perform("D".parse().unwrap()));
+        assert_eq!(states.len(), 3);
+    }
+
+    #[test]
+    fn perform_all() {
+        let scramble = super::parse_algorithm("R U2 F' Lw D B2 M' x").unwrap();
+        let scrambled = Cube::<3>::new().perform_all(scramble.clone());
+        assert_eq!(
+            scrambled,
+            scramble
+                .iter()
+                .fold(Cube::new(), |cube, &mv| cube.perform(mv))
+        );
+        assert_ne!(scrambled, Cube::new());
+        let undo = scramble.iter().rev().map(|mv| mv.inverse());
+        assert_eq!(scrambled.clone().perform_all(undo), Cube::new());
+
+        assert_eq!(Cube::<3>::new().apply_sequence(&scramble), scrambled);
+        let mut cube = Cube::<3>::new();
+        cube.apply_sequence_in_place(&scramble);
+        assert_eq!(cube, scrambled);
+        cube.apply_sequence_in_place(&super::invert_sequence(&scramble));
+        assert!(cube.is_solved());
+    }
+
+    #[test]
+    fn apply_algorithm() {
+        let alg = "3Rw U 3Rw' U'";
+        let cube = Cube::<5>::new().apply_algorithm(alg).unwrap();
+        let expected = [
+            Move::new(Axis::RL, RotationType::Normal, 0, 3),
+            "U".parse().unwrap(),
+            Move::new(Axis::RL, RotationType::Inverse, 0, 3),
+            "U'".parse().unwrap(),
+        ]
+        .into_iter()
+        .fold(Cube::<5>::new(), |cube, mv| cube.perform(mv));
+        assert_eq!(cube, expected);
+        assert_eq!(
+            Cube::<5>::new().apply_algorithm("3L 2Dw 3b M"),
+            Ok([
+                Move::new(Axis::RL, RotationType::Inverse, 2, 3),
+                Move::new(Axis::UD, RotationType::Inverse, 3, 5),
+                Move::new(Axis::FB, RotationType::Inverse, 2, 5),
+                Move::new(Axis::RL, RotationType::Inverse, 2, 3),
+            ]
+            .into_iter()
+            .fold(Cube::<5>::new(), |cube, mv| cube.perform(mv)))
+        );
+
+        let error = Cube::<3>::new().apply_algorithm(alg).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "cannot apply 3Rw: a wide move cannot turn 3 layers of a cube of size 3"
+        );
+        assert_eq!(
+            Cube::<3>::new()
+                .apply_algorithm("R 4R")
+                .unwrap_err()
+                .to_string(),
+            "cannot apply 4R: cannot turn layers 3 to 4 of a cube of size 3"
+        );
+        assert_eq!(
+            Cube::<4>::new().apply_algorithm("R M").unwrap_err(),
+            AlgorithmError {
+                token: "M".to_string(),
+                error: MoveError::InvalidNotation
+            }
+        );
+    }
+
+    #[test]
+    fn one_layer() {
+        // Every move of a single layer turns the whole cube.
+        let cube = Cube::<1>::new().apply_algorithm("R U'").unwrap();
+        assert_eq!(
+            cube,
+            ["x", "y'"]
+                .map(|mv| Move::parse_sized(mv, 1).unwrap())
+                .into_iter()
+                .fold(Cube::<1>::new(), Cube::perform)
+        );
+        // There is no middle layer to turn, or second layer to turn with the first.
+        for alg in ["M", "E'", "S2", "Rw", "2R"] {
+            assert!(Cube::<1>::new().apply_algorithm(alg).is_err());
+        }
+        assert_eq!(Move::parse_sized("M", 1), Err(MoveError::InvalidNotation));
+    }
+
+    #[test]
+    fn layers_out_of_range() {
+        // Layers deeper than the cube are rejected on every face, rather than wrapping round.
+        for (mv, start_depth, end_depth) in
+            [("5L", 4, 5), ("4L", 3, 4), ("5R", 4, 5), ("4D'", 3, 4)]
+        {
+            assert_eq!(
+                Move::parse_sized(mv, 3).unwrap_err().to_string(),
+                format!(
+                    "cannot turn layers {} to {} of a cube of size 3",
+                    start_depth, end_depth
+                )
+            );
+            assert!(mv.parse::<Move>().is_err());
+            assert!(Cube::<3>::new().apply_algorithm(mv).is_err());
+        }
+        assert_eq!(
+            Move::parse_sized("0Rw", 3),
+            Err(MoveError::TooWide {
+                layers: 0,
+                cube_size: 3
+            })
+        );
+        assert_eq!(Move::parse_sized("0L", 3), Err(MoveError::InvalidNotation));
+        assert_eq!(Move::parse_sized("3L", 3), Move::parse_sized("R'", 3));
+    }
+
+    #[test]
+    fn orient_face_up() {
+        let scrambled = Cube::<3>::new().apply_algorithm("R U2 F' L D B2").unwrap();
+        for face in FaceType::enumerate() {
+            let (oriented, moves) = scrambled.clone().orient_face_up(face);
+            assert_eq!(oriented.face(U)[(1, 1)], scrambled.face(face)[(1, 1)]);
+            assert_eq!(
+                oriented,
+                moves
+                    .iter()
+                    .fold(scrambled.clone(), |cube, &mv| cube.perform(mv))
+            );
+        }
+        let (oriented, moves) = scrambled.clone().orient_face_up(D);
+        assert_eq!(moves, vec!["x2".parse().unwrap()]);
+        assert_eq!(oriented.face(U)[(1, 1)], Colour::Yellow);
+        assert_eq!(scrambled.clone().orient_face_up(U), (scrambled, Vec::new()));
+
+        let (oriented, moves) = Cube::<4>::new().orient_face_up(L);
+        assert_eq!(moves, vec![Move::new(FB, RotationType::Normal, 0, 4)]);
+        assert!((0..4).all(|i| (0..4).all(|j| oriented.face(U)[(i, j)] == Colour::Orange)));
+    }
+
+    #[test]
+    fn apply_facelet_permutation() {
+        let scrambled = Cube::<3>::new().apply_algorithm("R U2 F' L D B2").unwrap();
+        for mv in ["R", "U'", "M2", "x", "Fw"] {
+            let mv = mv.parse::<Move>().unwrap();
+            let mut cube = scrambled.clone();
+            cube.apply_facelet_permutation(&mv.permutation_array(3))
+                .unwrap();
+            assert_eq!(cube, scrambled.clone().perform(mv));
+        }
+
+        let mut cube = scrambled.clone();
+        let mut perm = (0..54).collect::<Vec<_>>();
+        perm[10] = 11;
+        assert_eq!(
+            cube.apply_facelet_permutation(&perm)
+                .unwrap_err()
+                .to_string(),
+            "facelet 11 is not used exactly once in the permutation"
+        );
+        perm[10] = 54;
+        assert!(cube.apply_facelet_permutation(&perm).is_err());
+        assert_eq!(
+            cube.apply_facelet_permutation(&perm[1..])
+                .unwrap_err()
+                .to_string(),
+            "expected a permutation of 54 facelets but found 53"
+        );
+        assert_eq!(cube, scrambled);
+    }
+
+    #[test]
+    fn wide_facelets() {
+        let rw = Cube::<4>::new().apply_algorithm("Rw").unwrap();
+        let expected = [
+            "UUFF".repeat(4),
+            "R".repeat(16),
+            "FFDD".repeat(4),
+            "DDBB".repeat(4),
+            "L".repeat(16),
+            "UUBB".repeat(4),
+        ]
+        .concat();
+        assert_eq!(rw, Cube::from_facelets(&expected).unwrap());
+        // The R face turns with the block, so a sticker placed on it moves around the face.
+        let mut marked = Cube::<4>::new();
+        marked.face_mut(R)[(0, 0)] = Colour::White;
+        let turned = marked.perform("Rw".parse().unwrap());
+        assert_eq!(turned.face(R)[(0, 3)], Colour::White);
+        assert_eq!(turned.face(R)[(0, 0)], Colour::Red);
+
+        // A single inner layer leaves the R face alone.
+        let inner = Cube::<4>::new().apply_algorithm("2R").unwrap();
+        assert_eq!(inner.face(R), Cube::<4>::new().face(R));
+        assert_eq!(Cube::<4>::new().apply_algorithm("2R R").unwrap(), rw);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot turn layers 0 to 5 of a cube of size 4")]
+    fn too_deep() {
+        Cube::<4>::new().perform(Move::new(RL, RotationType::Normal, 0, 5));
+    }
+
+    #[test]
+    fn wide_constructor() {
+        let rw = Move::wide(R, RotationType::Normal, 2, 3).unwrap();
+        assert_eq!(rw, "Rw".parse().unwrap());
+        let scrambled = Cube::<3>::new().apply_algorithm("R U2 F' L D B2").unwrap();
+        assert_eq!(
+            scrambled.clone().perform(rw),
+            scrambled.clone().apply_algorithm("R M'").unwrap()
+        );
+        for (face, rotation_type, alg) in [
+            (L, RotationType::Inverse, "L' M'"),
+            (D, RotationType::Double, "D2 E2"),
+            (F, RotationType::Inverse, "F' S'"),
+            (B, RotationType::Normal, "B S'"),
+        ] {
+            let mv = Move::wide(face, rotation_type, 2, 3).unwrap();
+            assert_eq!(
+                scrambled.clone().perform(mv),
+                scrambled.clone().apply_algorithm(alg).unwrap(),
+                "{}",
+                alg
+            );
+        }
+
+        let scrambled = Cube::<5>::new().apply_algorithm("R 2U F' 3L D B2").unwrap();
+        for (face, rotation_type, depth, alg) in [
+            (R, RotationType::Normal, 3, "R 2R 3R"),
+            (B, RotationType::Inverse, 2, "B' 2B'"),
+            (D, RotationType::Double, 4, "D2 2D2 3D2 4D2"),
+        ] {
+            let mv = Move::wide(face, rotation_type, depth, 5).unwrap();
+            assert_eq!(
+                scrambled.clone().perform(mv),
+                scrambled.clone().apply_algorithm(alg).unwrap(),
+                "{}",
+                alg
+            );
+        }
+        assert_eq!(Move::wide(R, RotationType::Normal, 3, 3), None);
+        assert_eq!(Move::wide(R, RotationType::Normal, 0, 3), None);
+    }
+
+    #[test]
+    fn notation_round_trip() {
+        for n in 1..=7 {
+            for axis in [FB, RL, UD] {
+                for rotation_type in [
+                    RotationType::Normal,
+                    RotationType::Double,
+                    RotationType::Inverse,
+                ] {
+                    for start in 0..n {
+                        for end in start + 1..=n {
+                            let mv = Move::new(axis, rotation_type, start, end);
+                            let notation = mv.to_string_sized(n);
+                            let inner_block = start > 0 && end < n && end > start + 1;
+                            if inner_block && !(n % 2 == 1 && (start, end) == (n / 2, n / 2 + 1)) {
+                                assert!(Move::parse_sized(&notation, n).is_err(), "{}", notation);
+                            } else {
+                                assert_eq!(Move::parse_sized(&notation, n), Ok(mv), "{}", notation);
+                            }
+                            if n == 3 {
+                                assert_eq!(notation, mv.to_string());
+                                assert_eq!(Move::parse(&notation), Ok(mv));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        let notation = |alg: &str| {
+            let n = 6;
+            alg.split(' ')
+                .map(|mv| Move::parse_sized(mv, n).unwrap().to_string_sized(n))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        assert_eq!(
+            notation("R U' F2 3Rw 2L' Dw2 4R y'"),
+            "R U' F2 3Rw 2L' Dw2 3L' y'"
+        );
+    }
+
+    #[test]
+    fn display_round_trip() {
+        for rotation_type in [
+            RotationType::Normal,
+            RotationType::Double,
+            RotationType::Inverse,
+        ] {
+            for (start, end) in [(0, 1), (0, 2), (1, 2), (1, 3), (2, 3), (0, 3)] {
+                let mv = Move::new(RL, rotation_type, start, end);
+                assert_eq!(super::parse_algorithm(&mv.to_string()), Ok(vec![mv]));
+            }
+        }
+        assert_eq!(Move::new(RL, RotationType::Inverse, 0, 1).to_string(), "R'");
+        assert_eq!(Move::new(RL, RotationType::Double, 0, 1).to_string(), "R2");
+        assert_eq!(
+            Move::new(RL, RotationType::Inverse, 0, 2).to_string(),
+            "Rw'"
+        );
+        assert_eq!(
+            Move::new(RL, RotationType::Double, 0, 3).to_string_sized(5),
+            "3Rw2"
+        );
+    }
+
+    #[test]
+    fn whole_cube_rotations() {
+        let rotations = super::parse_algorithm("x y' z2").unwrap();
+        assert_eq!(
+            rotations,
+            vec![
+                Move::new(RL, RotationType::Normal, 0, 3),
+                Move::new(UD, RotationType::Inverse, 0, 3),
+                Move::new(FB, RotationType::Double, 0, 3),
+            ]
+        );
+        for mv in rotations {
+            let rotated = Cube::<3>::new().perform(mv);
+            assert!(rotated.is_solved());
+            assert_ne!(rotated, Cube::new());
+        }
+
+        // y turns the cube like U, so the front face moves to the left.
+        let scrambled = Cube::<3>::new().apply_algorithm("R U2 F' L D B2").unwrap();
+        let turned = scrambled.clone().apply_algorithm("y").unwrap();
+        assert_eq!(turned.face(L), scrambled.face(F));
+        assert_eq!(turned.face(L)[(1, 1)], Colour::Green);
+        for rotation in ["x", "y", "z'"] {
+            assert_eq!(
+                scrambled
+                    .clone()
+                    .apply_algorithm(&[rotation; 4].join(" "))
+                    .unwrap(),
+                scrambled
+            );
+        }
+        // A rotation turns every layer of bigger cubes too.
+        let cube = Cube::<4>::new().apply_algorithm("x").unwrap();
+        assert_eq!(cube, Cube::new().apply_algorithm("R 2R 3R L'").unwrap());
+    }
+
+    #[test]
+    fn slice_directions() {
+        let scrambled = Cube::<3>::new().apply_algorithm("R U2 F' L D B2").unwrap();
+        let perform = |alg: &str| scrambled.clone().apply_algorithm(alg).unwrap();
+        // Each slice turns the same way as the first face named in its convention, and against the second.
+        for (slice, equivalent) in [
+            ("M", "R L' x'"),
+            ("E", "U D' y'"),
+            ("S", "F' B z"),
+            ("M' E2 S'", "R' L x U2 D2 y2 F B' z'"),
+        ] {
+            assert_eq!(perform(slice), perform(equivalent), "{}", slice);
+        }
+        for slice in ["M", "E'", "S2"] {
+            assert_eq!(perform(&[slice; 4].join(" ")), scrambled);
+        }
+    }
+
+    #[test]
+    fn slice_moves() {
+        let m = Move::slice(SliceType::M, RotationType::Normal, 3).unwrap();
+        assert_eq!(m, "M".parse().unwrap());
+        assert_eq!(
+            Move::slice(SliceType::E, RotationType::Inverse, 3),
+            "E'".parse().ok()
+        );
+        assert_eq!(
+            Move::slice(SliceType::S, RotationType::Double, 3),
+            "S2".parse().ok()
+        );
+        assert_eq!(Move::slice(SliceType::M, RotationType::Normal, 4), None);
+
+        let cube = Cube::<3>::new().perform(m);
+        for (face, colour) in [
+            (F, Colour::White),
+            (U, Colour::Blue),
+            (B, Colour::Yellow),
+            (D, Colour::Green),
+        ] {
+            for i in 0..3 {
+                // The back face is seen from behind, so its middle column is still the middle column.
+                assert_eq!(cube.face(face)[(i, 1)], colour);
+                assert_eq!(cube.face(face)[(i, 0)], face.solved_colour());
+                assert_eq!(cube.face(face)[(i, 2)], face.solved_colour());
+            }
+        }
+        assert_eq!(cube.face(L), Cube::<3>::new().face(L));
+        assert_eq!(cube.face(R), Cube::<3>::new().face(R));
+        assert_eq!(
+            (0..4).fold(Cube::<3>::new(), |cube, _| cube.perform(m)),
+            Cube::new()
+        );
+
+        let m5 = Move::slice(SliceType::M, RotationType::Normal, 5).unwrap();
+        assert_eq!((m5.start_depth, m5.end_depth), (2, 3));
+        let cube = Cube::<5>::new().perform(m5);
+        assert_eq!(cube.face(F)[(0, 2)], Colour::White);
+        assert_eq!(cube.face(F)[(0, 1)], Colour::Green);
+        assert_eq!(cube, Cube::<5>::new().apply_algorithm("M").unwrap());
+        assert_eq!(
+            (0..4).fold(Cube::<5>::new(), |cube, _| cube.perform(m5)),
+            Cube::new()
+        );
+    }
+
+    #[test]
+    fn parse() {
+        for (token, rotation_type, start_depth, end_depth) in [
+            ("R", RotationType::Normal, 0, 1),
+            ("R'", RotationType::Inverse, 0, 1),
+            ("R2", RotationType::Double, 0, 1),
+            ("U2'", RotationType::Double, 0, 1),
+            ("2R", RotationType::Normal, 1, 2),
+            ("Rw'", RotationType::Inverse, 0, 2),
+            ("2Rw", RotationType::Normal, 0, 2),
+            ("L", RotationType::Inverse, 2, 3),
+        ] {
+            let mv = Move::parse(token).unwrap();
+            assert_eq!(
+                (mv.rotation_type, mv.start_depth, mv.end_depth),
+                (rotation_type, start_depth, end_depth),
+                "{}",
+                token
+            );
+            assert_eq!(token.parse(), Ok(mv));
+        }
+        assert_eq!(Move::parse("F").unwrap().axis, Axis::FB);
+
+        for (token, message) in [
+            ("Q", "unknown face 'Q'"),
+            ("R!", "unexpected '!' after the face"),
+            ("", "not a valid move"),
+            (
+                "3Rw",
+                "a wide move cannot turn 3 layers of a cube of size 3",
+            ),
+        ] {
+            let error = Move::parse(token).unwrap_err();
+            assert_eq!(error.token, token);
+            assert_eq!(error.error.to_string(), message);
+            assert!(token.parse::<Move>().is_err());
+        }
+    }
+
+    #[test]
+    fn parse_algorithm() {
+        let sexy = super::parse_algorithm("R  U R'   U'").unwrap();
+        assert_eq!(sexy, "R U R' U'".parse::<MoveSequence>().unwrap().moves);
+        let cube = (0..6)
+            .flat_map(|_| sexy.iter().copied())
+            .fold(Cube::<3>::new(), Cube::perform);
+        assert_eq!(cube, Cube::new());
+
+        assert_eq!(super::parse_algorithm(""), Ok(Vec::new()));
+        // Parsing a `MoveSequence` reports the same errors.
+        assert_eq!(
+            "R U  Q".parse::<MoveSequence>().unwrap_err(),
+            super::parse_algorithm("R U  Q").unwrap_err()
+        );
+        assert_eq!("R U  Q".parse::<MoveSequence>().unwrap_err().position, 5);
+        assert_eq!(" R\tU ".parse::<MoveSequence>().unwrap().moves.len(), 2);
+        assert_eq!(super::parse_algorithm("   "), Ok(Vec::new()));
+        let error = super::parse_algorithm("R U R' Q U' X").unwrap_err();
+        assert_eq!((error.token.as_str(), error.index), ("Q", 3));
+
+        assert_eq!(
+            super::parse_algorithm("R U R' U' F2 Dw\tr' M2 E S'\n"),
+            Ok("R U R' U' F2 Dw Rw' M2 E S'"
+                .parse::<MoveSequence>()
+                .unwrap()
+                .moves)
+        );
+        for (input, position) in [("R3", 0), ("U  Q", 3), ("R U\tR3 Q", 4)] {
+            assert_eq!(
+                super::parse_algorithm(input).unwrap_err().position,
+                position
+            );
+        }
+    }
+
+    #[test]
+    fn validate_algorithm() {
+        assert_eq!(
+            super::validate_algorithm("  R U'\tF2 "),
+            Ok("R U' F2".parse::<MoveSequence>().unwrap().moves)
+        );
+        let errors = super::validate_algorithm("R Q U' R3 F  Uw'' Kp").unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                ParseMoveError {
+                    token: "Q".to_string(),
+                    position: 2,
+                    index: 1,
+                    error: MoveError::UnknownFace('Q')
+                },
+                ParseMoveError {
+                    token: "R3".to_string(),
+                    position: 7,
+                    index: 3,
+                    error: MoveError::InvalidModifier('3')
+                },
+                ParseMoveError {
+                    token: "Kp".to_string(),
+                    position: 18,
+                    index: 6,
+                    error: MoveError::UnknownFace('K')
+                },
+            ]
+        );
+        assert_eq!(
+            errors[1].to_string(),
+            "invalid move 'R3' at position 7: unexpected '3' after the face"
+        );
+    }
+
+    #[test]
+    fn apply_until_solved() {
+        let sexy = "R U R' U'".parse::<MoveSequence>().unwrap().moves;
+        assert_eq!(
+            super::apply_until_solved(Cube::<3>::new(), &sexy, 10),
+            Some(6)
+        );
+        assert_eq!(super::apply_until_solved(Cube::<3>::new(), &sexy, 5), None);
+        // R U has order 105.
+        let r_u = "R U".parse::<MoveSequence>().unwrap().moves;
+        assert_eq!(super::apply_until_solved(Cube::<3>::new(), &r_u, 100), None);
+        assert_eq!(
+            super::apply_until_solved(Cube::<3>::new(), &r_u, 105),
+            Some(105)
+        );
+    }
+
+    #[test]
+    fn solved_with_edits() {
+        // Swap two stickers between U and F.
+        let cube =
+            Cube::<3>::solved_with_edits(&[(U, 0, 0, Colour::Green), (F, 0, 1, Colour::White)])
+                .unwrap();
+        assert_eq!(cube.face(U)[(0, 0)], Colour::Green);
+        assert_eq!(cube.face(F)[(0, 1)], Colour::White);
+        assert_eq!(cube.face(U)[(0, 1)], Colour::White);
+        assert_eq!(cube.face(F)[(0, 0)], Colour::Green);
+
+        let edits = [(U, 0, 0, Colour::Green)];
+        assert_eq!(
+            Cube::<3>::solved_with_edits(&edits).unwrap_err(),
+            CubeValidationError::WrongColourCount {
+                colour: Colour::Green,
+                found: 10,
+                expected: 9
+            }
+        );
+        let cube = Cube::<3>::solved_with_edits_unchecked(&edits);
+        assert_eq!(cube.face(U)[(0, 0)], Colour::Green);
+    }
+
+    #[test]
+    fn resolve_centers() {
+        let scheme = FaceType::enumerate().map(FaceType::solved_colour);
+        // Outer layer moves keep the centres of a reduced cube together.
+        let reduced = Cube::<4>::new().apply_algorithm("R U2 F' L D B2").unwrap();
+        assert_eq!(reduced.center_color_of(U), Some(Colour::White));
+        assert_eq!(reduced.resolve_centers(), Some(scheme));
+        let rotated = reduced.apply_algorithm("x").unwrap();
+        assert_eq!(rotated.center_color_of(U), Some(Colour::Green));
+        assert_eq!(
+            rotated.resolve_centers(),
+            Some([F, R, U, B, L, D].map(|face| rotated.face(face)[(1, 1)]))
+        );
+
+        // Two centres are unclear, but the others decide them.
+        let mut scanned = Cube::<4>::new();
+        scanned.face_mut(U)[(1, 1)] = Colour::Green;
+        scanned.face_mut(U)[(2, 2)] = Colour::Green;
+        scanned.face_mut(F)[(1, 1)] = Colour::White;
+        scanned.face_mut(F)[(2, 2)] = Colour::White;
+        assert_eq!(scanned.center_color_of(U), None);
+        assert_eq!(scanned.center_color_of(F), None);
+        assert_eq!(scanned.center_color_of(R), Some(Colour::Red));
+        assert_eq!(scanned.resolve_centers(), Some(scheme));
+        // With only the U and D centres known, the cube could be turned any way about the vertical axis.
+        for face in [F, R, B, L] {
+            scanned.face_mut(face)[(1, 2)] = Colour::Yellow;
+            scanned.face_mut(face)[(2, 1)] = Colour::Yellow;
+        }
+        scanned.face_mut(U)[(1, 1)] = Colour::White;
+        assert_eq!(scanned.center_color_of(U), Some(Colour::White));
+        assert_eq!(scanned.resolve_centers(), None);
+
+        // On odd cubes the fixed centre counts like any other centre sticker.
+        let cube = Cube::<5>::new().apply_algorithm("y' 2R").unwrap();
+        assert_eq!(cube.center_color_of(U), Some(Colour::White));
+        assert_eq!(cube.center_color_of(F), Some(Colour::Orange));
+        assert_eq!(Cube::<2>::new().center_color_of(U), None);
+    }
+
+    #[test]
+    fn try_validate() {
+        assert_eq!(Cube::<3>::new().try_validate(), Ok(()));
+        assert_eq!(
+            Cube::<3>::new()
+                .apply_algorithm("x y2 M")
+                .unwrap()
+                .try_validate(),
+            Ok(())
+        );
+        // One sticker recoloured.
+        let mut cube = Cube::<3>::new();
+        cube.face_mut(R)[(2, 0)] = Colour::Blue;
+        assert_eq!(
+            cube.try_validate(),
+            Err(CubeValidationError::WrongColourCount {
+                colour: Colour::Red,
+                found: 8,
+                expected: 9
+            })
+        );
+        // Swapping two centres keeps the colour counts, but no real cube looks like this.
+        let cube = Cube::<3>::solved_with_edits_unchecked(&[
+            (U, 1, 1, Colour::Green),
+            (F, 1, 1, Colour::White),
+        ]);
+        assert_eq!(
+            cube.try_validate(),
+            Err(CubeValidationError::CentresNotStandard)
+        );
+        // Recolouring a centre and a sticker of that colour keeps the counts, but repeats a centre.
+        let cube = Cube::<3>::solved_with_edits_unchecked(&[
+            (U, 1, 1, Colour::Green),
+            (F, 0, 1, Colour::White),
+        ]);
+        assert_eq!(
+            cube.try_validate(),
+            Err(CubeValidationError::DuplicateCenter)
+        );
+        let cube = Cube::<5>::solved_with_edits_unchecked(&[
+            (R, 2, 2, Colour::Orange),
+            (L, 2, 2, Colour::Red),
+        ]);
+        assert_eq!(
+            cube.try_validate(),
+            Err(CubeValidationError::CentresNotStandard)
+        );
+        // Even cubes have no fixed centres to check.
+        let cube = Cube::<4>::solved_with_edits_unchecked(&[
+            (R, 1, 1, Colour::Orange),
+            (L, 1, 1, Colour::Red),
+        ]);
+        assert_eq!(cube.try_validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate() {
+        Cube::<2>::new().validate();
+        Cube::<3>::new().validate();
+        Cube::<3>::new()
+            .apply_algorithm("R U M' x")
+            .unwrap()
+            .validate();
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid cube: expected 9 Red stickers but found 8")]
+    fn validate_corrupted() {
+        let mut cube = Cube::<3>::new();
+        cube.face_mut(R)[(2, 0)] = Colour::Blue;
+        cube.validate();
+    }
+
+    #[test]
+    fn solved_color_at() {
+        let rotated = Cube::<3>::new().perform("x".parse().unwrap());
+        for cube in [Cube::<3>::new(), rotated] {
+            for face in FaceType::enumerate() {
+                for i in 0..3 {
+                    for j in 0..3 {
+                        assert_eq!(cube.solved_color_at(face, i, j), cube.face(face)[(i, j)]);
+                    }
+                }
+            }
+        }
+        let r = Cube::<3>::new().perform("R".parse().unwrap());
+        assert_eq!(r.solved_color_at(F, 0, 2), Colour::Green);
+        assert_eq!(r.face(F)[(0, 2)], Colour::Yellow);
+    }
+
+    #[test]
+    fn mirrored() {
+        let moves = super::parse_algorithm("R U' Lw F2 x M' y S B' E D").unwrap();
+        let mirrored_moves = super::parse_algorithm("L' U Rw' F2 x M' y' S' B E' D'").unwrap();
+        assert_eq!(
+            moves.iter().map(|mv| mv.mirrored(3)).collect::<Vec<_>>(),
+            mirrored_moves
+        );
+        let cube = Cube::<3>::new().perform_all(moves);
+        assert_eq!(cube.mirrored(), Cube::new().perform_all(mirrored_moves));
+        assert_eq!(cube.mirrored().mirrored(), cube);
+
+        let moves = [Move::new(RL, RotationType::Normal, 1, 2)];
+        assert_eq!(
+            moves[0].mirrored(4),
+            Move::new(RL, RotationType::Normal, 2, 3)
+        );
+        let cube = Cube::<4>::new().apply_algorithm("2R U 3Fw' Dw").unwrap();
+        assert_eq!(
+            cube.mirrored(),
+            Cube::new().apply_algorithm("2L' U' 3Fw Dw'").unwrap()
+        );
+    }
+
+    #[test]
+    fn is_solved() {
+        let cube = Cube::<3>::new();
+        assert!(cube.is_solved());
+        let turned = cube.perform("R".parse().unwrap());
+        assert!(!turned.is_solved());
+        assert!(turned.perform("R'".parse().unwrap()).is_solved());
+        assert!(Cube::<3>::new()
+            .apply_algorithm("x y'")
+            .unwrap()
+            .is_solved());
+        assert!(!Cube::<3>::new().apply_algorithm("M").unwrap().is_solved());
+        assert!(Cube::<4>::new().apply_algorithm("z2").unwrap().is_solved());
+        assert!(!Cube::<4>::new().apply_algorithm("2R").unwrap().is_solved());
+        assert!(Cube::<1>::new().apply_algorithm("x").unwrap().is_solved());
+    }
+
+    #[test]
+    fn is_face_solved() {
+        let perform = |alg: &str| {
+            alg.parse::<MoveSequence>()
+                .unwrap()
+                .moves
+                .into_iter()
+                .fold(Cube::<3>::new(), |cube, mv| cube.perform(mv))
+        };
+        for cube in [Cube::<3>::new(), perform("x y")] {
+            assert!(FaceType::enumerate()
+                .iter()
+                .all(|&face| cube.is_face_solved(face)));
+        }
+
+        // A T permutation leaves the U face a single colour, but swaps pieces around it.
+        let t_perm = perform("R U R' U' R' F R2 U' R' U' R U R' F'");
+        assert!(t_perm
+            .face(U)
+            .rows
+            .iter()
+            .flatten()
+            .all(|&c| c == Colour::White));
+        assert!(!t_perm.is_face_solved(U));
+        assert!(t_perm.is_face_solved(D));
+        assert!(!t_perm.is_face_solved(F));
+
+        let u = perform("U");
+        assert!(!u.is_face_solved(U));
+        assert!(u.is_face_solved(D));
+        assert!(!u.is_face_solved(R));
+
+        let r = Cube::<4>::new().perform("R".parse().unwrap());
+        assert!(r.is_face_solved(L));
+        assert!(!r.is_face_solved(R));
+        assert!(!r.is_face_solved(U));
+    }
+
+    #[test]
+    fn basic_moves() {
+        let moves = all_basic_moves();
+        assert_eq!(
+            MoveSequence {
+                moves: moves.to_vec()
+            }
+            .to_string(),
+            "U U2 U' D D2 D' F F2 F' B B2 B' R R2 R' L L2 L'"
+        );
+        for (i, &a) in moves.iter().enumerate() {
+            assert!(moves[i + 1..].iter().all(|&b| a != b));
+            assert_ne!(Cube::<3>::new().perform(a), Cube::new());
+        }
+    }
+
+    #[test]
+    fn face_moves() {
+        for mv in all_basic_moves() {
+            let four = (0..4).fold(Cube::<3>::new(), |cube, _| cube.perform(mv));
+            assert_eq!(four, Cube::new(), "{} four times", mv);
+            assert_eq!(
+                Cube::<3>::new().perform(mv).perform(mv.inverse()),
+                Cube::new(),
+                "{} {}",
+                mv,
+                mv.inverse()
+            );
+        }
+
+        // Each face turns the stickers next to it the right way.
+        let perform = |mv: &str| Cube::<3>::new().perform(mv.parse().unwrap());
+        assert_eq!(perform("U").face(F).row(0), [Colour::Red; 3]);
+        assert_eq!(perform("D").face(F).row(2), [Colour::Orange; 3]);
+        assert_eq!(perform("L").face(F).col(0), [Colour::White; 3]);
+        assert_eq!(perform("B").face(U).row(0), [Colour::Red; 3]);
+    }
+
+    #[test]
+    fn back_layer_directions() {
+        // Turning a far face must also turn its own stickers, which a solved cube would not show,
+        // so one corner sticker of the turning face is marked white.
+        let cases = [
+            (
+                "B",
+                B,
+                "RRRUUUUUURRDRRDRRDFFFFFFFFFDDDDDDLLLULLULLULLBBUBBBBBB",
+            ),
+            (
+                "L",
+                L,
+                "BUUBUUBUURRRRRRRRRUFFUFFUFFFDDFDDFDDLLULLLLLLBBDBBDBBD",
+            ),
+            (
+                "D",
+                D,
+                "UUUUUUUUURRRRRRFFFFFFFFFLLLDDUDDDDDDLLLLLLBBBBBBBBBRRR",
+            ),
+        ];
+        for (mv, face, facelets) in cases {
+            let mv = mv.parse::<Move>().unwrap();
+            let marked = Cube::<3>::solved_with_edits_unchecked(&[(face, 0, 0, Colour::White)]);
+            assert_eq!(marked.clone().perform(mv).to_facelets(), facelets, "{}", mv);
+            assert_eq!(marked.clone().perform(mv).perform(mv.inverse()), marked);
+            assert_eq!(marked.clone().perform_all([mv; 4]), marked);
+        }
+    }
+
+    #[test]
+    fn scan_cube() {
+        let moves = "R U2 F' L D B2 M E' S"
+            .parse::<MoveSequence>()
+            .unwrap()
+            .moves;
+        let start = Cube::<3>::new().perform("x".parse().unwrap());
+        let states = moves.iter().scan_cube(start.clone()).collect::<Vec<_>>();
+        assert_eq!(states, start.trace(&moves)[1..]);
+        // Moves may also be given by value.
+        assert_eq!(
+            moves.into_iter().scan_cube(start).collect::<Vec<_>>(),
+            states
+        );
+    }
+
+    #[test]
+    fn permutation_array() {
+        fn check<const N: usize>(moves: &str) {
+            let scrambled = "R U2 F' L D B2"
+                .parse::<MoveSequence>()
+                .unwrap()
+                .moves
+                .into_iter()
+                .fold(Cube::<N>::new(), |cube, mv| cube.perform(mv));
+            let facelets = |cube: &Cube<N>| {
+                (0..6 * N * N)
+                    .map(|i| {
+                        let (face, row, col) = facelet_coords(i, N);
+                        cube.face(face)[(row, col)]
+                    })
+                    .collect::<Vec<_>>()
+            };
+            for mv in moves.parse::<MoveSequence>().unwrap().moves {
+                let before = facelets(&scrambled);
+                let permuted = mv
+                    .permutation_array(N)
+                    .into_iter()
+                    .map(|source| before[source])
+                    .collect::<Vec<_>>();
+                assert_eq!(permuted, facelets(&scrambled.clone().perform(mv)), "{}", mv);
+            }
+        }
+        let basic = MoveSequence {
+            moves: all_basic_moves().to_vec(),
+        }
+        .to_string();
+        check::<3>(&basic);
+        check::<3>("M M2 M' E E2 E' S S2 S' r u' f2 x y' z2");
+        // On a 4x4x4 cube, the moves on the far faces become turns of an inner slice.
+        check::<4>(&basic);
+    }
+
+    #[test]
+    fn equal_ignoring_face() {
+        let perform = |alg: &str| {
+            alg.parse::<MoveSequence>()
+                .unwrap()
+                .moves
+                .into_iter()
+                .fold(Cube::<3>::new(), |cube, mv| cube.perform(mv))
+        };
+        let t_perm = perform("R U R' U' R' F R2 U' R' U' R U R' F'");
+        assert!(t_perm.equal_ignoring_face(&Cube::new(), U));
+        assert!(perform("U").equal_ignoring_face(&Cube::new(), U));
+        assert!(!t_perm.equal_ignoring_face(&Cube::new(), D));
+        assert!(!perform("R").equal_ignoring_face(&Cube::new(), U));
+        assert!(perform("R").equal_ignoring_face(&Cube::new(), R));
+
+        let mut cube = Cube::<4>::new();
+        cube.face_mut(F)[(0, 2)] = Colour::White;
+        assert!(cube.equal_ignoring_face(&Cube::new(), U));
+        cube.face_mut(F)[(1, 2)] = Colour::White;
+        assert!(!cube.equal_ignoring_face(&Cube::new(), U));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn from_face_json() {
+        let cube = "R U2 F' L D B2"
+            .parse::<MoveSequence>()
+            .unwrap()
+            .moves
+            .into_iter()
+            .fold(Cube::<3>::new(), |cube, mv| cube.perform(mv));
+        let to_json = |name: fn(Colour) -> String| {
+            let faces = FaceType::enumerate().map(|face| {
+                let rows = cube
+                    .face(face)
+                    .rows
+                    .map(|row| row.map(|colour| format!("\"{}\"", name(colour))).join(", "));
+                format!("\"{}\": [[{}]]", face, rows.join("], ["))
+            });
+            format!("{{ {} }}", faces.join(", "))
+        };
+        let letters = to_json(|colour| colour.letter().to_string());
+        let names = to_json(|colour| format!("{:?}", colour));
+        assert_eq!(Cube::from_face_json(&letters), Ok(cube.clone()));
+        assert_eq!(Cube::from_face_json(&names), Ok(cube.clone()));
+
+        let missing = letters.replace("\"B\"", "\"X\"");
+        assert_eq!(
+            Cube::<3>::from_face_json(&missing),
+            Err(CubeError::Facelets(FaceletError::MissingFace { face: B }))
+        );
+        let invalid = names.replacen("Green", "Purple", 1);
+        assert!(matches!(
+            Cube::<3>::from_face_json(&invalid),
+            Err(CubeError::Facelets(FaceletError::InvalidColour { .. }))
+        ));
+        let unbalanced = names.replacen("Green", "Red", 1);
+        assert!(matches!(
+            Cube::<3>::from_face_json(&unbalanced),
+            Err(CubeError::Invalid(
+                CubeValidationError::WrongColourCount { .. }
+            ))
+        ));
+        assert_eq!(
+            Cube::<3>::from_face_json("[1, 2, 3]"),
+            Err(CubeError::Facelets(FaceletError::InvalidJson))
+        );
+        assert_eq!(
+            Cube::<2>::from_face_json(&letters),
+            Err(CubeError::Facelets(FaceletError::WrongFaceSize {
+                face: F,
+                expected: 2
+            }))
+        );
+    }
+
+    #[test]
+    fn rotate_face_only() {
+        let scrambled = "R U2 F' L D B2"
+            .parse::<MoveSequence>()
+            .unwrap()
+            .moves
+            .into_iter()
+            .fold(Cube::<3>::new(), |cube, mv| cube.perform(mv));
+        for (rotation, mv) in [
+            (RotationType::Normal, "R"),
+            (RotationType::Double, "R2"),
+            (RotationType::Inverse, "R'"),
+        ] {
+            let mut cube = scrambled.clone();
+            cube.rotate_face_only(R, rotation);
+            // The R face turns just as it does in a real move, but none of the neighbouring stickers move.
+            let turned = scrambled.clone().perform(mv.parse().unwrap());
+            assert_eq!(cube.face(R), turned.face(R));
+            for face in [F, U, B, L, D] {
+                assert_eq!(cube.face(face), scrambled.face(face));
+            }
+        }
+    }
+
+    #[test]
+    fn reduce_to_3x3() {
+        assert_eq!(Cube::<4>::new().reduce_to_3x3(), Some(Cube::new()));
+
+        // Outer layer turns keep the cube reduced.
+        let moves = "R U F' U2 R' F2".parse::<MoveSequence>().unwrap().moves;
+        let big = moves
+            .iter()
+            .fold(Cube::<4>::new(), |cube, &mv| cube.perform(mv));
+        let small = moves
+            .iter()
+            .fold(Cube::<3>::new(), |cube, &mv| cube.perform(mv));
+        assert_eq!(big.reduce_to_3x3(), Some(small));
+
+        // Turning an inner slice breaks up the centres and edges.
+        let inner = Move::new(Axis::RL, RotationType::Normal, 1, 2);
+        assert_eq!(big.perform(inner).reduce_to_3x3(), None);
+    }
+
+    #[test]
+    fn solved_fraction() {
+        assert_eq!(Cube::<3>::new().solved_fraction(), 1.0);
+        assert_eq!(Cube::<4>::new().solved_fraction(), 1.0);
+        // R moves twelve of the 48 non-centre stickers.
+        let r = Cube::<3>::new().perform("R".parse().unwrap());
+        assert_eq!(r.solved_fraction(), 36.0 / 48.0);
+    }
+
+    #[test]
+    fn from_facelets() {
+        let solved = "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB";
+        assert_eq!(Cube::<3>::from_facelets(solved), Ok(Cube::new()));
+        // After R, the F face takes the colours of D in its right column, and so on.
+        let r = "UUFUUFUUFRRRRRRRRRFFDFFDFFDDDBDDBDDBLLLLLLLLLUBBUBBUBB";
+        assert_eq!(
+            Cube::<3>::from_facelets(r),
+            Ok(Cube::new().perform("R".parse().unwrap()))
+        );
+
+        assert_eq!(
+            Cube::<3>::from_facelets("UUU"),
+            Err(FaceletError::WrongLength {
+                expected: 54,
+                found: 3
+            })
+        );
+        assert_eq!(
+            Cube::<3>::from_facelets(&solved.replace("RRRRRRRRR", "RRRRXRRRR")),
+            Err(FaceletError::InvalidCharacter {
+                index: 13,
+                character: 'X'
+            })
+        );
+    }
+
+    #[test]
+    fn to_facelets() {
+        assert_eq!(
+            Cube::<3>::new().to_facelets(),
+            "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB"
+        );
+        let r = Cube::<3>::new().perform("R".parse().unwrap());
+        assert_eq!(
+            r.to_facelets(),
+            "UUFUUFUUFRRRRRRRRRFFDFFDFFDDDBDDBDDBLLLLLLLLLUBBUBBUBB"
+        );
+        let cube = Cube::<3>::new()
+            .apply_algorithm("R U2 F' L D B2 M' x")
+            .unwrap();
+        assert_eq!(Cube::from_facelets(&cube.to_facelets()), Ok(cube));
+        let cube = Cube::<4>::new().apply_algorithm("Rw U2 2F' L D").unwrap();
+        assert_eq!(Cube::from_facelets(&cube.to_facelets()), Ok(cube));
+    }
+
+    #[test]
+    fn facelets_round_trip() {
+        use rand::{rngs::SmallRng, SeedableRng};
+
+        fn round_trip<const N: usize>(rng: &mut SmallRng) {
+            let cube = Cube::<N>::new().perform_all(Cube::<N>::scramble(rng, 30));
+            let facelets = cube.to_facelets();
+            assert_eq!(facelets.len(), 6 * N * N);
+            assert_eq!(Cube::<N>::from_facelets(&facelets), Ok(cube));
+            assert_eq!(
+                Cube::<N>::from_facelets(&facelets[1..]),
+                Err(FaceletError::WrongLength {
+                    expected: 6 * N * N,
+                    found: 6 * N * N - 1
+                })
+            );
+        }
+        let mut rng = SmallRng::seed_from_u64(4);
+        round_trip::<2>(&mut rng);
+        round_trip::<3>(&mut rng);
+        round_trip::<4>(&mut rng);
+    }
+
+    #[test]
+    fn facelet_indices() {
+        for index in 0..54 {
+            let (face, row, col) = facelet_coords(index, 3);
+            assert_eq!(facelet_index(face, row, col, 3), index);
+        }
+        assert_eq!(facelet_coords(0, 3), (U, 0, 0));
+        assert_eq!(facelet_index(R, 1, 0, 3), 12);
+        assert_eq!(facelet_index(B, 2, 2, 3), 53);
+        assert_eq!(facelet_coords(13, 2), (D, 0, 1));
+    }
+
+    #[test]
+    fn faces_turned() {
+        let faces = |alg: &str, n| {
+            Move::parse_sized(alg, n)
+                .map(|mv| (mv.face(), mv.faces_turned(n)))
+                .unwrap()
+        };
+        assert_eq!(faces("R", 3), (R, vec![R]));
+        assert_eq!(faces("L'", 3), (L, vec![L]));
+        assert_eq!(faces("M", 3), (L, vec![R, L]));
+        assert_eq!(faces("E2", 3), (D, vec![U, D]));
+        assert_eq!(faces("x", 3), (R, vec![]));
+        assert_eq!(faces("Rw", 4), (R, vec![R]));
+        assert_eq!(faces("2R", 4), (L, vec![R, L]));
+        assert_eq!(faces("Lw", 4), (L, vec![L]));
+        assert_eq!(faces("3Fw", 5), (F, vec![F]));
+        assert_eq!(faces("z'", 5), (F, vec![]));
+    }
+
+    #[test]
+    fn move_order() {
+        let canonical = "F F2 F' R R2 R' U U2 U' B B2 B' L L2 L' D D2 D'"
+            .parse::<MoveSequence>()
+            .unwrap()
+            .moves;
+        let mut moves = "L' U2 D R' B F2 U' L2 D2 F R2 B' U R L B2 D' F'"
+            .parse::<MoveSequence>()
+            .unwrap()
+            .moves;
+        moves.sort();
+        assert_eq!(moves, canonical);
+        moves.reverse();
+        moves.sort();
+        assert_eq!(moves, canonical);
+    }
+
+    #[test]
+    fn net_alignment() {
+        let cube = Cube::<3>::new();
+        // Solved cubes render exactly as they did with single-character cells.
+        assert_eq!(cube.to_string().lines().next().unwrap(), "      w w w ");
+
+        // Make one cell wider than the others, and check that every column still lines up.
+        let net = cube.render_net(|face, i, j| {
+            if (face, i, j) == (F, 1, 1) {
+                "10".to_string()
+            } else {
+                cube.face(face)[(i, j)].letter().to_string()
+            }
+        });
+        let lines = net.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 9);
+        // The U and D faces are indented by the width of the L face.
+        assert!(lines[0].starts_with(&" ".repeat(9)));
+        assert_eq!(lines[0].find('w'), Some(9));
+        assert_eq!(lines[6].find('y'), Some(9));
+        // The F face starts directly beneath the U face in every row.
+        for line in &lines[3..6] {
+            assert_eq!(line.len(), 4 * 3 * 3);
+            assert_eq!(&line[9..10], "g");
+        }
+        assert_eq!(&lines[4][12..14], "10");
+    }
+
+    #[test]
+    fn wide_moves_on_even_cubes() {
+        let rw = "Rw".parse::<Move>().unwrap();
+        let r = "R".parse::<Move>().unwrap();
+        let inner = Move::new(Axis::RL, RotationType::Normal, 1, 2);
+        assert_eq!((rw.start_depth, rw.end_depth), (0, 2));
+        assert_eq!(
+            Cube::<4>::new().perform(rw),
+            Cube::<4>::new().perform(r).perform(inner)
+        );
+        assert_eq!(
+            Cube::<4>::new().perform(rw),
+            Cube::<4>::new().perform(inner).perform(r)
+        );
+
+        // Exactly the two layers nearest R are turned: F takes the colour of D there,
+        // and the two layers nearest L are untouched.
+        let cube = Cube::<4>::new().perform(rw);
+        for i in 0..4 {
+            assert_eq!(cube.face(F)[(i, 0)], Colour::Green);
+            assert_eq!(cube.face(F)[(i, 1)], Colour::Green);
+            assert_eq!(cube.face(F)[(i, 2)], Colour::Yellow);
+            assert_eq!(cube.face(F)[(i, 3)], Colour::Yellow);
+        }
+        assert_eq!(cube.face(L), Cube::<4>::new().face(L));
+        assert_eq!(cube.face(R), Cube::<4>::new().perform(r).face(R));
+    }
+}
@@ -0,0 +1,44 @@
+use futures::{Stream, StreamExt};
+
+use crate::cube::{Cube, Move};
+
+/// Applies each move from the stream as it arrives,
+/// calling `on_state` with the state of the cube after every move.
+/// Returns the final state once the stream ends.
+pub async fn drive<const N: usize, S: Stream<Item = Move>>(
+    mut cube: Cube<N>,
+    stream: S,
+    mut on_state: impl FnMut(&Cube<N>),
+) -> Cube<N> {
+    futures::pin_mut!(stream);
+    while let Some(mv) = stream.next().await {
+        cube = cube.perform(mv);
+        on_state(&cube);
+    }
+    cube
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cube::MoveSequence;
+
+    use super::*;
+
+    #[test]
+    fn drive_matches_fold() {
+        let moves = "R U R' U' F2 D' L".parse::<MoveSequence>().unwrap().moves;
+        let mut states = Vec::new();
+        let cube = futures::executor::block_on(drive(
+            Cube::<3>::new(),
+            futures::stream::iter(moves.clone()),
+            |cube| states.push(cube.clone()),
+        ));
+
+        let expected = moves
+            .iter()
+            .fold(Cube::<3>::new(), |cube, &mv| cube.perform(mv));
+        assert_eq!(cube, expected);
+        assert_eq!(states.len(), moves.len());
+        assert_eq!(states.last(), Some(&expected));
+    }
+}
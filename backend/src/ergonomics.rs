@@ -0,0 +1,377 @@
+use std::{collections::HashMap, ops::ControlFlow};
+
+use crate::{
+    cube::{all_basic_moves, invert_sequence, Axis, Cube, FaceType, Move, RotationType},
+    group::{InverseSemigroup, Magma, Unital},
+    heuristic::permutation_qtm_distance_estimate,
+    permute::CubePermutation3,
+    solve::{solve_mirrored, SolveMethod},
+};
+
+/// Whether a move can be made from the standard grip, in which the right hand turns R and the
+/// index fingers turn U and F. Turning any other layer, such as B, L, D or a slice, needs a regrip.
+fn in_home_grip(mv: &Move) -> bool {
+    mv.start_depth == 0
+}
+
+/// Counts the regrips an algorithm forces, as a rough measure of how finger-friendly it is.
+/// The hands start in the standard grip, and each time the next move needs the other grip,
+/// the hands must be released and regripped.
+/// A direction reversal, a quarter turn the opposite way to the quarter turn before it as seen
+/// from the faces turned, also counts as a regrip, since the fingers must be moved to push the other way.
+/// Half turns can be made in either direction, so they never reverse.
+pub fn regrip_count(moves: &[Move]) -> usize {
+    let mut home = true;
+    let mut direction = None;
+    let mut count = 0;
+    for mv in moves {
+        if in_home_grip(mv) != home {
+            home = !home;
+            count += 1;
+        }
+        let rotation = mv.face_rotation();
+        if rotation != RotationType::Double {
+            if direction == Some(rotation.inverse()) {
+                count += 1;
+            }
+            direction = Some(rotation);
+        }
+    }
+    count
+}
+
+/// How many times each face is turned in a sequence of moves, for judging how well a solution
+/// is balanced between the hands. Faces that are never turned are left out.
+/// The counts add up to the length of the sequence in the half turn metric.
+pub fn face_usage(moves: &[Move]) -> HashMap<FaceType, usize> {
+    let mut usage = HashMap::new();
+    for face in moves.iter().flat_map(|mv| mv.faces_turned(3)) {
+        *usage.entry(face).or_insert(0) += 1;
+    }
+    usage
+}
+
+/// How many faces are turned on each axis in a sequence of moves, indexed by axis.
+pub fn axis_usage(moves: &[Move]) -> [usize; 3] {
+    let mut usage = [0; 3];
+    for mv in moves {
+        usage[mv.axis as usize] += mv.faces_turned(3).len();
+    }
+    usage
+}
+
+/// The cost of turning each face, used to find solutions that favour comfortable moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoveWeights {
+    /// The cost of turning each face, or a wide move on that face, indexed by face.
+    /// Every cost must be positive.
+    pub faces: [u32; 6],
+}
+
+impl Default for MoveWeights {
+    /// Every face costs the same, so the cheapest solution is the shortest.
+    fn default() -> Self {
+        Self { faces: [1; 6] }
+    }
+}
+
+impl MoveWeights {
+    /// Weights that make B, L and D cost `penalty` times as much as F, R and U.
+    pub fn ruf(penalty: u32) -> Self {
+        let mut faces = [1; 6];
+        for face in [FaceType::B, FaceType::L, FaceType::D] {
+            faces[face as usize] = penalty;
+        }
+        Self { faces }
+    }
+
+    pub fn weight(&self, mv: Move) -> u32 {
+        self.faces[mv.face() as usize]
+    }
+
+    /// The total cost of a sequence of moves. Whole cube rotations turn no faces, so they cost nothing.
+    pub fn cost(&self, moves: &[Move]) -> u32 {
+        moves
+            .iter()
+            .filter(|mv| !mv.faces_turned(3).is_empty())
+            .map(|&mv| self.weight(mv))
+            .sum()
+    }
+}
+
+/// The most moves, not counting a final rotation, that `solve_ergonomic` searches for before falling back to Roux.
+/// The search is exhaustive, so each extra move makes it take dozens of times longer.
+pub const MAX_ERGONOMIC_DEPTH: usize = 4;
+
+/// The state of a weighted iterative deepening A* search.
+struct ErgonomicSearch {
+    /// Each move with its permutation and cost.
+    moves: Vec<(Move, CubePermutation3, u32)>,
+    /// The 24 whole cube rotations, each with a sequence of rotations that performs it.
+    rotations: Vec<(CubePermutation3, Vec<Move>)>,
+    /// The cost of the cheapest move.
+    cheapest: u32,
+}
+
+impl ErgonomicSearch {
+    fn new(weights: MoveWeights) -> Self {
+        // Wide moves let the solver turn a comfortable face in place of an awkward one;
+        // for example, `r` has the same effect as `L` up to a rotation of the whole cube.
+        let moves = all_basic_moves()
+            .into_iter()
+            .chain(all_basic_moves().into_iter().map(|mv| Move {
+                start_depth: mv.start_depth.min(1),
+                end_depth: mv.end_depth.max(2),
+                ..mv
+            }))
+            .map(|mv| (mv, CubePermutation3::from_move(mv), weights.weight(mv)))
+            .collect::<Vec<_>>();
+        assert!(
+            moves.iter().all(|&(_, _, cost)| cost > 0),
+            "every move must have a positive cost"
+        );
+
+        let generators =
+            [Axis::RL, Axis::UD].map(|axis| Move::new(axis, RotationType::Normal, 0, 3));
+        let mut rotations = vec![(CubePermutation3::identity(), Vec::new())];
+        let mut i = 0;
+        while i < rotations.len() {
+            for generator in generators {
+                let rotation = CubePermutation3::from_move(generator).op(rotations[i].0);
+                if rotations.iter().all(|&(other, _)| other != rotation) {
+                    let mut sequence = rotations[i].1.clone();
+                    sequence.push(generator);
+                    rotations.push((rotation, sequence));
+                }
+            }
+            i += 1;
+        }
+
+        Self {
+            cheapest: moves.iter().map(|&(_, _, cost)| cost).min().unwrap(),
+            moves,
+            rotations,
+        }
+    }
+
+    /// The whole cube rotation that puts the centres where they are, and a sequence of rotations that performs it.
+    fn rotation(&self, state: CubePermutation3) -> &(CubePermutation3, Vec<Move>) {
+        self.rotations
+            .iter()
+            .find(|(rotation, _)| rotation.centres() == state.centres())
+            .expect("outer and wide moves only ever rotate the centres")
+    }
+
+    /// Undoes the whole cube rotation that puts the centres where they are,
+    /// so the cube is solved exactly when the result is the identity.
+    fn reoriented(&self, state: CubePermutation3) -> CubePermutation3 {
+        self.rotation(state).0.inverse().op(state)
+    }
+
+    /// A lower bound on the number of moves needed to solve the cube, as each move is at most two quarter turns.
+    fn moves_lower_bound(reoriented: &CubePermutation3) -> usize {
+        permutation_qtm_distance_estimate(reoriented).div_ceil(2)
+    }
+
+    /// A lower bound on the cost of solving the cube, as each move costs at least as much as the cheapest move.
+    fn lower_bound(&self, reoriented: &CubePermutation3) -> u32 {
+        Self::moves_lower_bound(reoriented) as u32 * self.cheapest
+    }
+
+    /// Searches for a solution costing at most `bound` and using at most `MAX_ERGONOMIC_DEPTH` moves,
+    /// appending its moves to `path`.
+    /// If there is none, returns the smallest cost that exceeded the bound,
+    /// or `u32::MAX` if every solution is too long.
+    fn search(
+        &self,
+        state: CubePermutation3,
+        cost: u32,
+        bound: u32,
+        path: &mut Vec<Move>,
+    ) -> ControlFlow<(), u32> {
+        let reoriented = self.reoriented(state);
+        let estimate = cost + self.lower_bound(&reoriented);
+        if estimate > bound {
+            return ControlFlow::Continue(estimate);
+        }
+        if reoriented == CubePermutation3::identity() {
+            return ControlFlow::Break(());
+        }
+        if path.len() + Self::moves_lower_bound(&reoriented) > MAX_ERGONOMIC_DEPTH {
+            return ControlFlow::Continue(u32::MAX);
+        }
+
+        let mut next_bound = u32::MAX;
+        for &(mv, permutation, move_cost) in &self.moves {
+            // Moves on the same axis commute, so only try them in one order.
+            if let Some(last) = path.last() {
+                if last.axis == mv.axis
+                    && (last.start_depth, last.end_depth) >= (mv.start_depth, mv.end_depth)
+                {
+                    continue;
+                }
+            }
+            path.push(mv);
+            match self.search(permutation.op(state), cost + move_cost, bound, path) {
+                ControlFlow::Break(()) => return ControlFlow::Break(()),
+                ControlFlow::Continue(exceeded) => next_bound = next_bound.min(exceeded),
+            }
+            path.pop();
+        }
+        ControlFlow::Continue(next_bound)
+    }
+}
+
+/// Finds the solution to a 3x3x3 cube with the lowest total cost under the given weights,
+/// using outer layer and wide moves. This can be used to prefer comfortable faces such as R, U and F.
+/// Wide moves rotate the centres, so the solution ends with the whole cube rotations, which cost nothing,
+/// that return the cube to its original orientation.
+/// The search is exhaustive, so it only looks for solutions of up to `MAX_ERGONOMIC_DEPTH` moves.
+/// If there is none, the cube is solved with Roux, either as usual or mirrored,
+/// whichever costs less under the weights.
+///
+/// The result is deterministic. Moves are tried in a fixed order: the outer layer moves in the order of
+/// `all_basic_moves`, then the wide moves on the same faces in the same order.
+/// Of the cheapest solutions, the one returned is the first in this order, comparing move by move,
+/// where moves on the same axis are always written with the shallower layer first.
+///
+/// Returns None only if the cube cannot be solved.
+pub fn solve_ergonomic(cube: Cube<3>, weights: MoveWeights) -> Option<Vec<Move>> {
+    if !cube.solvability_report().is_solvable() {
+        return None;
+    }
+    let start = CubePermutation3::from_cube(&cube)?;
+    let search = ErgonomicSearch::new(weights);
+
+    let mut bound = search.lower_bound(&search.reoriented(start));
+    let mut path = Vec::new();
+    while bound != u32::MAX {
+        match search.search(start, 0, bound, &mut path) {
+            ControlFlow::Break(()) => {
+                let end = path.iter().fold(start, |state, &mv| {
+                    CubePermutation3::from_move(mv).op(state)
+                });
+                path.extend(invert_sequence(&search.rotation(end).1));
+                return Some(path);
+            }
+            ControlFlow::Continue(next_bound) => bound = next_bound,
+        }
+    }
+
+    let usual = SolveMethod::Roux.solve(&cube)?.moves;
+    let mirrored = solve_mirrored(cube)?;
+    Some(std::cmp::min_by_key(usual, mirrored, |moves| {
+        weights.cost(moves)
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        cube::{Colour, MoveSequence},
+        grade::half_turn_metric,
+    };
+
+    use super::*;
+
+    fn regrips(alg: &str) -> usize {
+        regrip_count(&alg.parse::<MoveSequence>().unwrap().moves)
+    }
+
+    #[test]
+    fn finger_friendly() {
+        assert_eq!(regrips("R U R' U'"), 1);
+        assert_eq!(regrips("R U F R' F'"), 1);
+        assert_eq!(regrips("L' U' L U"), 5);
+        assert!(regrips("R U R' U'") < regrips("R B L' U' B' L"));
+
+        // Only the directions differ, so only the reversals are counted.
+        assert_eq!(regrips("R U R U"), 0);
+        assert_eq!(regrips("R U' R U'"), 3);
+        assert_eq!(regrips("R U2 R' U2 R"), 2);
+        assert_eq!(regrips("L M' L'"), 2);
+    }
+
+    #[test]
+    fn usage() {
+        // A Roux solution, including a rotation and slice moves.
+        let moves = "x' U' R2 F r U' R' U2 M' U M U2 R U R' U' R' F R F' M2 U M2 U2"
+            .parse::<MoveSequence>()
+            .unwrap()
+            .moves;
+        let faces = face_usage(&moves);
+        assert_eq!(faces[&FaceType::R], 11);
+        assert_eq!(faces[&FaceType::L], 4);
+        assert_eq!(faces[&FaceType::U], 9);
+        assert_eq!(faces[&FaceType::F], 3);
+        assert!(!faces.contains_key(&FaceType::B));
+        assert!(!faces.contains_key(&FaceType::D));
+        assert_eq!(axis_usage(&moves), [3, 15, 9]);
+        assert_eq!(faces.values().sum::<usize>(), half_turn_metric(&moves));
+    }
+
+    #[test]
+    fn deterministic() {
+        for (scramble, expected) in [("R L", "R' L'"), ("U D2 F", "F' U' D2")] {
+            let cube = Cube::new().apply_algorithm(scramble).unwrap();
+            let first = solve_ergonomic(cube.clone(), MoveWeights::default()).unwrap();
+            let second = solve_ergonomic(cube, MoveWeights::default()).unwrap();
+            assert_eq!(first, second);
+            assert_eq!(MoveSequence { moves: first }.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn prefers_comfortable_faces() {
+        let awkward = |moves: &[Move]| {
+            moves
+                .iter()
+                .filter(|&&mv| matches!(mv.face(), FaceType::B | FaceType::L | FaceType::D))
+                .count()
+        };
+        let (mut unweighted_awkward, mut weighted_awkward) = (0, 0);
+        for scramble in ["L", "D' R", "B2 U", "L D B", "R2 D F'"] {
+            let cube = Cube::new().apply_algorithm(scramble).unwrap();
+            let unweighted = solve_ergonomic(cube.clone(), MoveWeights::default()).unwrap();
+            let weighted = solve_ergonomic(cube.clone(), MoveWeights::ruf(5)).unwrap();
+            for solution in [&unweighted, &weighted] {
+                // Any rotation made by the wide moves is undone.
+                assert_eq!(cube.clone().apply_sequence(solution), Cube::new());
+            }
+            let turns = |moves: &[Move]| {
+                moves
+                    .iter()
+                    .filter(|mv| mv.end_depth - mv.start_depth < 3)
+                    .count()
+            };
+            assert!(turns(&unweighted) <= turns(&weighted));
+            unweighted_awkward += awkward(&unweighted);
+            weighted_awkward += awkward(&weighted);
+        }
+        assert!(weighted_awkward < unweighted_awkward);
+    }
+
+    #[test]
+    fn ergonomic_limits() {
+        // The search is bounded, so cubes far from solved are solved with Roux instead.
+        for alg in [
+            "R U F L D B",
+            "B R2 U2 F R' U' B2 F U R2 U2 L' D' R2 D L R' F' R F2 B2 U D' R L2",
+        ] {
+            let far = Cube::new().apply_algorithm(alg).unwrap();
+            let roux = SolveMethod::Roux.solve(&far).unwrap().moves;
+            for weights in [MoveWeights::default(), MoveWeights::ruf(5)] {
+                let solution = solve_ergonomic(far.clone(), weights).unwrap();
+                assert_eq!(far.clone().apply_sequence(&solution), Cube::new());
+                assert!(weights.cost(&solution) <= weights.cost(&roux));
+            }
+        }
+
+        let twisted = Cube::<3>::solved_with_edits_unchecked(&[
+            (FaceType::U, 2, 2, Colour::Green),
+            (FaceType::F, 0, 2, Colour::Red),
+            (FaceType::R, 0, 0, Colour::White),
+        ]);
+        assert_eq!(solve_ergonomic(twisted, MoveWeights::default()), None);
+    }
+}
@@ -0,0 +1,55 @@
+use std::fmt::Display;
+
+use crate::cube::{CornerType, EdgeType};
+
+/// One of the four slots filled during F2L, named after its middle layer edge.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum F2lSlot {
+    FR,
+    FL,
+    BR,
+    BL,
+}
+
+impl F2lSlot {
+    /// The corner and edge that belong in this slot.
+    pub fn pair(self) -> (CornerType, EdgeType) {
+        match self {
+            F2lSlot::FR => (CornerType::FDR, EdgeType::FR),
+            F2lSlot::FL => (CornerType::FDL, EdgeType::FL),
+            F2lSlot::BR => (CornerType::BDR, EdgeType::BR),
+            F2lSlot::BL => (CornerType::BDL, EdgeType::BL),
+        }
+    }
+}
+
+impl Display for F2lSlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Works out which slot a corner and edge belong in, by the colours on the pieces.
+/// Returns None if the pieces do not form an F2L pair.
+pub fn home_slot(corner: CornerType, edge: EdgeType) -> Option<F2lSlot> {
+    [F2lSlot::FR, F2lSlot::FL, F2lSlot::BR, F2lSlot::BL]
+        .into_iter()
+        .find(|slot| slot.pair() == (corner, edge))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn home_slots() {
+        assert_eq!(home_slot(CornerType::FDR, EdgeType::FR), Some(F2lSlot::FR));
+        assert_eq!(home_slot(CornerType::FDL, EdgeType::FL), Some(F2lSlot::FL));
+        assert_eq!(home_slot(CornerType::BDR, EdgeType::BR), Some(F2lSlot::BR));
+        assert_eq!(home_slot(CornerType::BDL, EdgeType::BL), Some(F2lSlot::BL));
+
+        assert_eq!(home_slot(CornerType::FDR, EdgeType::FL), None);
+        assert_eq!(home_slot(CornerType::FUR, EdgeType::FR), None);
+        assert_eq!(home_slot(CornerType::FDR, EdgeType::DR), None);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::{
+    cube::{Cube, Move, MoveSequence},
+    group::InverseSemigroup,
+    path::{shortest_path, MAX_DEPTH},
+    solve::SolveMethod,
+};
+
+/// How a solution to a scramble compares to a reference solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolutionGrade {
+    /// Whether the solution actually solves the scramble.
+    pub solves: bool,
+    /// The length of the solution in the half turn metric.
+    pub move_count: usize,
+    /// The length of the reference solution in the half turn metric.
+    pub reference_count: usize,
+    /// Whether the reference solution is known to be optimal.
+    pub reference_optimal: bool,
+    /// Runs of moves in the solution that could be written with fewer moves,
+    /// given by the index of their first move and the moves themselves.
+    pub redundancies: Vec<(usize, Vec<Move>)>,
+}
+
+impl SolutionGrade {
+    /// How many more moves the solution took than the reference solution.
+    pub fn excess_moves(&self) -> isize {
+        self.move_count as isize - self.reference_count as isize
+    }
+}
+
+/// The length of a sequence of moves in the half turn metric, in which each turn of a face counts as one.
+/// Slice moves turn two faces, and whole cube rotations turn none.
+pub fn half_turn_metric(moves: &[Move]) -> usize {
+    moves.iter().map(|mv| mv.faces_turned(3).len()).sum()
+}
+
+/// The length of a sequence of moves in the quarter turn metric, in which each half turn counts as two.
+pub fn quarter_turn_metric(moves: &[Move]) -> usize {
+    moves
+        .iter()
+        .map(|mv| mv.faces_turned(3).len() * mv.rotation_type.rotations().unsigned_abs() as usize)
+        .sum()
+}
+
+/// Finds runs of moves on the same axis that cancel or merge into fewer moves.
+pub(crate) fn redundancies(moves: &[Move]) -> Vec<(usize, Vec<Move>)> {
+    let mut result = Vec::new();
+    let mut start = 0;
+    while start < moves.len() {
+        let mut end = start + 1;
+        while end < moves.len() && moves[end].axis == moves[start].axis {
+            end += 1;
+        }
+        let run = MoveSequence {
+            moves: moves[start..end].to_vec(),
+        };
+        if run.clone().canonicalise().moves.len() < run.moves.len() {
+            result.push((start, run.moves));
+        }
+        start = end;
+    }
+    result
+}
+
+/// Grades a solution to a scramble on a 3x3x3 cube.
+/// When the scramble is short enough, the reference solution is optimal.
+/// Otherwise, it is the shorter of the Roux solution and undoing the scramble.
+pub fn grade_solution(scramble: &[Move], user_solution: &[Move]) -> SolutionGrade {
+    let scrambled = scramble
+        .iter()
+        .fold(Cube::<3>::new(), |cube, &mv| cube.perform(mv));
+    let solved = user_solution
+        .iter()
+        .fold(scrambled.clone(), |cube, &mv| cube.perform(mv));
+
+    let undo = MoveSequence {
+        moves: scramble.to_vec(),
+    }
+    .inverse()
+    .canonicalise();
+    // The search is only guaranteed to finish when undoing the scramble is within its reach.
+    let optimal = if undo.moves.len() <= MAX_DEPTH {
+        shortest_path(&scrambled, &Cube::new())
+    } else {
+        None
+    };
+    let (reference_count, reference_optimal) = match optimal {
+        Some(moves) => (half_turn_metric(&moves), true),
+        None => {
+            let roux = SolveMethod::Roux
+                .solve(&scrambled)
+                .map(|solution| half_turn_metric(&solution.moves));
+            let undo = half_turn_metric(&undo.moves);
+            (roux.map_or(undo, |roux| roux.min(undo)), false)
+        }
+    };
+
+    SolutionGrade {
+        solves: solved == Cube::new(),
+        move_count: half_turn_metric(user_solution),
+        reference_count,
+        reference_optimal,
+        redundancies: redundancies(user_solution),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn moves(alg: &str) -> Vec<Move> {
+        alg.parse::<MoveSequence>().unwrap().moves
+    }
+
+    #[test]
+    fn optimal_solution() {
+        let grade = grade_solution(&moves("R U F'"), &moves("F U' R'"));
+        assert!(grade.solves);
+        assert_eq!(grade.move_count, 3);
+        assert_eq!(grade.reference_count, 3);
+        assert!(grade.reference_optimal);
+        assert_eq!(grade.excess_moves(), 0);
+        assert!(grade.redundancies.is_empty());
+    }
+
+    #[test]
+    fn cancellations() {
+        let grade = grade_solution(&moves("R U F'"), &moves("F U' U U' R' L R R' L'"));
+        assert!(grade.solves);
+        assert_eq!(grade.move_count, 9);
+        assert_eq!(grade.excess_moves(), 6);
+        assert_eq!(
+            grade.redundancies,
+            vec![(1, moves("U' U U'")), (4, moves("R' L R R' L'"))]
+        );
+    }
+
+    #[test]
+    fn not_a_solution() {
+        let grade = grade_solution(&moves("R U F'"), &moves("F U R'"));
+        assert!(!grade.solves);
+    }
+
+    #[test]
+    fn metrics() {
+        let alg = moves("R U2 M' x y' r2");
+        assert_eq!(half_turn_metric(&alg), 5);
+        assert_eq!(quarter_turn_metric(&alg), 7);
+    }
+
+    #[test]
+    fn long_scramble() {
+        let scramble = moves("R U F' L2 D B' R2 U' L F2 D' B U2");
+        let undo = MoveSequence {
+            moves: scramble.clone(),
+        }
+        .inverse();
+        let grade = grade_solution(&scramble, &undo.moves);
+        assert!(grade.solves);
+        assert!(!grade.reference_optimal);
+        assert!(grade.reference_count <= 13);
+        assert!(grade.excess_moves() >= 0);
+    }
+}
@@ -131,11 +131,30 @@ impl<const K: u8> Display for CyclicGroup<K> {
     }
 }
 
+/// Given the image of each index under a permutation, works out whether the permutation is odd.
+/// A permutation of `n` elements with `c` cycles is the product of `n - c` transpositions.
+fn permutation_is_odd(images: impl Iterator<Item = usize>) -> bool {
+    let images = images.collect::<Vec<_>>();
+    let mut visited = vec![false; images.len()];
+    let mut cycles = 0;
+    for start in 0..images.len() {
+        if !visited[start] {
+            cycles += 1;
+            let mut i = start;
+            while !visited[i] {
+                visited[i] = true;
+                i = images[i];
+            }
+        }
+    }
+    (images.len() - cycles) % 2 == 1
+}
+
 /// Represents an element from the symmetric group on `S`.
 ///
 /// Note that this symmetric group acts like a group, that is, in cycle notation,
 /// `(a b) (b c) = (a b c)` - the symmetries act in reverse order.
-#[derive(PartialEq, Eq, Clone, Copy)]
+#[derive(PartialEq, Eq, Clone, Copy, Hash)]
 pub struct SymmetricGroup<S>
 where
     S: Enumerable,
@@ -155,6 +174,11 @@ where
     pub fn new_unchecked(map: [S; S::N]) -> Self {
         Self { map }
     }
+
+    /// Returns true if this permutation is odd, that is, a product of an odd number of transpositions.
+    pub fn is_odd(&self) -> bool {
+        permutation_is_odd(self.map.iter().map(|s| s.index()))
+    }
 }
 
 impl<S> Default for SymmetricGroup<S>
@@ -262,7 +286,7 @@ where
 ///
 /// Note that this symmetric group acts like a group, that is, in cycle notation,
 /// `(a b) (b c) = (a b c)` - the symmetries act in reverse order.
-#[derive(PartialEq, Eq, Clone, Copy)]
+#[derive(PartialEq, Eq, Clone, Copy, Hash)]
 pub struct OrientedSymmetricGroup<S, const K: u8>
 where
     S: Enumerable,
@@ -282,6 +306,19 @@ where
     pub fn new_unchecked(map: [(S, CyclicGroup<K>); S::N]) -> Self {
         Self { map }
     }
+
+    /// Returns true if the underlying permutation (ignoring orientation) is odd.
+    pub fn is_odd(&self) -> bool {
+        permutation_is_odd(self.map.iter().map(|(s, _)| s.index()))
+    }
+
+    /// The sum of the orientations of every element.
+    /// On a real cube, this is always the identity.
+    pub fn orientation_sum(&self) -> CyclicGroup<K> {
+        self.map
+            .iter()
+            .fold(CyclicGroup::identity(), |sum, &(_, r)| sum.op(r))
+    }
 }
 
 impl<S, const K: u8> Default for OrientedSymmetricGroup<S, K>
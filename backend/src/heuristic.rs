@@ -0,0 +1,113 @@
+use std::collections::VecDeque;
+
+use crate::{
+    cube::{all_basic_moves, Cube, RotationType},
+    group::{CyclicGroup, Enumerable, GroupAction, Magma, Unital},
+    permute::{CornerCubelet, CubePermutation3, EdgeCubelet},
+};
+
+/// Encodes the orientations of the corners at the first seven positions, in base 3.
+/// The last orientation is determined by the others.
+fn corner_orientation_index(permutation: &CubePermutation3) -> usize {
+    CornerCubelet::enumerate()[..7]
+        .iter()
+        .fold(0, |index, &position| {
+            let (_, orientation) = permutation
+                .corners()
+                .unact(&(position, CyclicGroup::identity()));
+            index * 3 + orientation.get_value() as usize
+        })
+}
+
+/// Encodes the orientations of the edges at the first eleven positions, in base 2.
+/// The last orientation is determined by the others.
+fn edge_orientation_index(permutation: &CubePermutation3) -> usize {
+    EdgeCubelet::enumerate()[..11]
+        .iter()
+        .fold(0, |index, &position| {
+            let (_, orientation) = permutation
+                .edges()
+                .unact(&(position, CyclicGroup::identity()));
+            index * 2 + orientation.get_value() as usize
+        })
+}
+
+/// Builds a pattern database: the exact number of quarter turns needed to solve each value of
+/// `index`, found by searching outwards from the solved cube.
+fn pattern_database(size: usize, index: fn(&CubePermutation3) -> usize) -> Vec<u8> {
+    let moves = all_basic_moves()
+        .into_iter()
+        .filter(|mv| mv.rotation_type != RotationType::Double)
+        .map(CubePermutation3::from_move)
+        .collect::<Vec<_>>();
+
+    let mut distances = vec![u8::MAX; size];
+    let mut queue = VecDeque::new();
+    distances[index(&CubePermutation3::identity())] = 0;
+    queue.push_back((CubePermutation3::identity(), 0));
+    while let Some((state, distance)) = queue.pop_front() {
+        for mv in &moves {
+            let next = mv.op(state);
+            let next_index = index(&next);
+            if distances[next_index] == u8::MAX {
+                distances[next_index] = distance + 1;
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+    distances
+}
+
+lazy_static::lazy_static! {
+    static ref CORNER_ORIENTATION_DISTANCES: Vec<u8> =
+        pattern_database(3usize.pow(7), corner_orientation_index);
+    static ref EDGE_ORIENTATION_DISTANCES: Vec<u8> =
+        pattern_database(2usize.pow(11), edge_orientation_index);
+}
+
+/// A lower bound on the number of quarter turns needed to solve the cube.
+/// The estimate never overestimates: it is the larger of the exact number of quarter turns needed
+/// to orient the corners, and to orient the edges.
+/// Cubes whose stickers do not form a valid set of pieces are estimated to be solved.
+pub fn qtm_distance_estimate(cube: &Cube<3>) -> usize {
+    CubePermutation3::from_cube(cube).map_or(0, |permutation| {
+        permutation_qtm_distance_estimate(&permutation)
+    })
+}
+
+/// As `qtm_distance_estimate`, for a cube already given as a permutation.
+pub fn permutation_qtm_distance_estimate(permutation: &CubePermutation3) -> usize {
+    let corners = CORNER_ORIENTATION_DISTANCES[corner_orientation_index(permutation)];
+    let edges = EDGE_ORIENTATION_DISTANCES[edge_orientation_index(permutation)];
+    corners.max(edges) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cube::MoveSequence;
+
+    use super::*;
+
+    #[test]
+    fn estimates() {
+        assert_eq!(qtm_distance_estimate(&Cube::new()), 0);
+        for scramble in [
+            "F",
+            "R U",
+            "F R' U2 L",
+            "R U2 F' L D B2 R' U",
+            "B R2 U2 F R' U' B2 F U R2 U2 L' D' R2 D L R' F' R F2 B2 U D' R L2",
+        ] {
+            let moves = scramble.parse::<MoveSequence>().unwrap().moves;
+            let cube = moves.iter().fold(Cube::new(), |cube, &mv| cube.perform(mv));
+            // Performing the scramble backwards solves the cube, so the optimal solution is no longer than that.
+            let quarter_turns = moves
+                .iter()
+                .map(|mv| mv.rotation_type.rotations().unsigned_abs() as usize)
+                .sum::<usize>();
+            let estimate = qtm_distance_estimate(&cube);
+            assert!(estimate <= quarter_turns);
+            assert!(estimate > 0);
+        }
+    }
+}
@@ -0,0 +1,819 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+use crate::{
+    cube::{
+        Axis, Colour, CornerType::*, Cube3, EdgeType::*, FaceType, Move, MoveSequence, RotationType,
+    },
+    group::{CyclicGroup, Enumerable, GroupAction, InverseSemigroup, Magma, Unital},
+    intuitive::{SequenceGraph, SequenceSolver},
+    permute::{CornerCubelet, CubePermutation3, EdgeCubelet},
+};
+
+/// The positions and orientations of the four U layer corners.
+type LlCornerSignature = [(CornerCubelet, CyclicGroup<3>); 4];
+
+fn ll_corner_signature(permutation: &CubePermutation3) -> LlCornerSignature {
+    [FUL, FUR, BUR, BUL].map(|corner| {
+        permutation
+            .corners()
+            .act(&(CornerCubelet(corner), CyclicGroup::identity()))
+    })
+}
+
+lazy_static::lazy_static! {
+    /// The first two layers are assumed to be solved.
+    /// The FDR slot is the keyhole: a corner at FUR is taken down through it with `R' D R` or `R' D' R`,
+    /// where the D turn chooses which first layer corner comes up in its place, and U turns bring
+    /// the next corner to FUR. Corners are twisted by repeating `R' D' R D`, and cycled by
+    /// inserting corners through the keyhole one after another.
+    /// Each generator restores the first two layers and the last layer edges.
+    static ref KEYHOLE_CORNERS: SequenceSolver<LlCornerSignature> = {
+        let gen_set = vec![
+            "U",
+            // Twist FUR clockwise and FUL anticlockwise.
+            "R' D' R D R' D' R D U R' D' R D R' D' R D R' D' R D R' D' R D U'",
+            // Cycle FUL, FUR and BUL.
+            "R' D R U2 R' D' R U R' D R U R' D' R",
+        ]
+            .into_iter()
+            .map(|x| x.parse::<MoveSequence>().unwrap())
+            .collect::<Vec<_>>();
+
+        let graph = SequenceGraph::new("keyhole_corners", gen_set, |cube| {
+            ll_corner_signature(&cube)
+        });
+        graph.search(ll_corner_signature(&CubePermutation3::identity()), |seq| {
+            seq.moves.len() as u64
+        })
+    };
+}
+
+/// One of the 57 OLL cases, numbered in the usual way from 1 to 57.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct OllCase(u8);
+
+/// Rotation-free algorithms for each OLL case, in order.
+const OLL_ALGS: [&str; 57] = [
+    "R U2 R2 F R F' U2 R' F R F'",
+    "F R U R' U' F' f R U R' U' f'",
+    "f R U R' U' f' U' F R U R' U' F'",
+    "f R U R' U' f' U F R U R' U' F'",
+    "r' U2 R U R' U r",
+    "r U2 R' U' R U' r'",
+    "r U R' U R U2 r'",
+    "l' U' L U' L' U2 l",
+    "R U R' U' R' F R2 U R' U' F'",
+    "R U R' U R' F R F' R U2 R'",
+    "r U R' U R' F R F' R U2 r'",
+    "M' R' U' R U' R' U2 R U' R r'",
+    "F U R U' R2 F' R U R U' R'",
+    "R' F R U R' F' R F U' F'",
+    "r' U' r R' U' R U r' U r",
+    "r U r' R U R' U' r U' r'",
+    "R U R' U R' F R F' U2 R' F R F'",
+    "r U R' U R U2 r2 U' R U' R' U2 r",
+    "M U R U R' U' M' R' F R F'",
+    "r U R' U' M2 U R U' R' U' M'",
+    "R U2 R' U' R U R' U' R U' R'",
+    "R U2 R2 U' R2 U' R2 U2 R",
+    "R2 D' R U2 R' D R U2 R",
+    "r U R' U' r' F R F'",
+    "F' r U R' U' r' F R",
+    "R U2 R' U' R U' R'",
+    "R U R' U R U2 R'",
+    "r U R' U' M U R U' R'",
+    "R U R' U' R U' R' F' U' F R U R'",
+    "F R' F R2 U' R' U' R U R' F2",
+    "R' U' F U R U' R' F' R",
+    "L U F' U' L' U L F L'",
+    "R U R' U' R' F R F'",
+    "R U R2 U' R' F R U R U' F'",
+    "R U2 R2 F R F' R U2 R'",
+    "L' U' L U' L' U L U L F' L' F",
+    "F R' F' R U R U' R'",
+    "R U R' U R U' R' U' R' F R F'",
+    "L F' L' U' L U F U' L'",
+    "R' F R U R' U' F' U R",
+    "R U R' U R U2 R' F R U R' U' F'",
+    "R' U' R U' R' U2 R F R U R' U' F'",
+    "F' U' L' U L F",
+    "F U R U' R' F'",
+    "F R U R' U' F'",
+    "R' U' R' F R F' U R",
+    "R' U' R' F R F' R' F R F' U R",
+    "F R U R' U' R U R' U' F'",
+    "r U' r2 U r2 U r2 U' r",
+    "r' U r2 U' r2 U' r2 U r'",
+    "F U R U' R' U R U' R' F'",
+    "R U R' U R U' B U' B' R'",
+    "l' U2 L U L' U' L U L' U l",
+    "r U2 R' U' R U R' U' R U' r'",
+    "R' F R U R U' R2 F' R2 U' R' U R U R'",
+    "r' U' r U' R' U R U' R' U R r' U r",
+    "R U R' U' M' U R U' r'",
+];
+
+impl OllCase {
+    /// The OLL case with the given number, from 1 to 57.
+    pub fn new(number: u8) -> Option<Self> {
+        if (1..=57).contains(&number) {
+            Some(Self(number))
+        } else {
+            None
+        }
+    }
+
+    pub fn number(self) -> u8 {
+        self.0
+    }
+
+    /// An algorithm that orients the last layer from this case.
+    pub fn alg(self) -> MoveSequence {
+        OLL_ALGS[self.index()].parse().unwrap()
+    }
+}
+
+impl Enumerable for OllCase {
+    const N: usize = 57;
+
+    fn enumerate() -> [Self; Self::N] {
+        let mut result = [Self(0); Self::N];
+        for (i, case) in result.iter_mut().enumerate() {
+            *case = Self::from_index(i);
+        }
+        result
+    }
+
+    fn from_index(idx: usize) -> Self {
+        Self(idx as u8 + 1)
+    }
+
+    fn index(&self) -> usize {
+        self.0 as usize - 1
+    }
+}
+
+impl Display for OllCase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "OLL {}", self.0)
+    }
+}
+
+/// One of the 21 PLL cases.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[repr(u8)]
+#[rustfmt::skip]
+pub enum PllCase {
+    Aa, Ab, E, F, Ga, Gb, Gc, Gd, H, Ja, Jb,
+    Na, Nb, Ra, Rb, T, Ua, Ub, V, Y, Z,
+}
+
+impl PllCase {
+    /// A rotation-free algorithm that permutes the last layer from this case.
+    pub fn alg(self) -> MoveSequence {
+        match self {
+            PllCase::Aa => "R' F R' B2 R F' R' B2 R2",
+            PllCase::Ab => "R B' R F2 R' B R F2 R2",
+            PllCase::E => "R B' R' F R B R' F' R B R' F R B' R' F'",
+            PllCase::F => "R' U' F' R U R' U' R' F R2 U' R' U' R U R' U R",
+            PllCase::Ga => "R2 U R' U R' U' R U' R2 U' D R' U R D'",
+            PllCase::Gb => "R' U' R U D' R2 U R' U R U' R U' R2 D",
+            PllCase::Gc => "R2 U' R U' R U R' U R2 U D' R U' R' D",
+            PllCase::Gd => "R U R' U' D R2 U' R U' R' U R' U R2 D'",
+            PllCase::H => "M2 U M2 U2 M2 U M2",
+            PllCase::Ja => "R' U L' U2 R U' R' U2 R L",
+            PllCase::Jb => "R U R' F' R U R' U' R' F R2 U' R'",
+            PllCase::Na => "R U R' U R U R' F' R U R' U' R' F R2 U' R' U2 R U' R'",
+            PllCase::Nb => "R' U R U' R' F' U' F R U R' F R' F' R U' R",
+            PllCase::Ra => "R U' R' U' R U R D R' U' R D' R' U2 R'",
+            PllCase::Rb => "R2 F R U R U' R' F' R U2 R' U2 R",
+            PllCase::T => "R U R' U' R' F R2 U' R' U' R U R' F'",
+            PllCase::Ua => "M2 U M U2 M' U M2",
+            PllCase::Ub => "M2 U' M U2 M' U' M2",
+            PllCase::V => "R' U R' U' B' R' B2 U' B' U B' R B R",
+            PllCase::Y => "F R U' R' U' R U R' F' R U R' U' R' F R F'",
+            PllCase::Z => "M2 U M2 U M' U2 M2 U2 M'",
+        }
+        .parse()
+        .unwrap()
+    }
+}
+
+impl Enumerable for PllCase {
+    const N: usize = 21;
+
+    #[rustfmt::skip]
+    fn enumerate() -> [Self; Self::N] {
+        use PllCase::*;
+        [
+            Aa, Ab, E, F, Ga, Gb, Gc, Gd, H, Ja, Jb,
+            Na, Nb, Ra, Rb, T, Ua, Ub, V, Y, Z,
+        ]
+    }
+
+    fn from_index(idx: usize) -> PllCase {
+        Self::enumerate()[idx]
+    }
+
+    fn index(&self) -> usize {
+        *self as u8 as usize
+    }
+}
+
+impl Display for PllCase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// A last layer case that can be solved with a single OLL or PLL algorithm.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LastLayerCase {
+    Oll(OllCase),
+    Pll(PllCase),
+}
+
+impl LastLayerCase {
+    pub fn alg(self) -> MoveSequence {
+        match self {
+            LastLayerCase::Oll(case) => case.alg(),
+            LastLayerCase::Pll(case) => case.alg(),
+        }
+    }
+}
+
+impl Display for LastLayerCase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LastLayerCase::Oll(case) => write!(f, "{}", case),
+            LastLayerCase::Pll(case) => write!(f, "{}-perm", case),
+        }
+    }
+}
+
+/// The orientations of the pieces in each last layer position (edges UR UF UL UB, then corners FUL FUR BUR BUL).
+type OllSignature = ([CyclicGroup<2>; 4], [CyclicGroup<3>; 4]);
+/// The positions of each last layer piece (edges UR UF UL UB, then corners FUL FUR BUR BUL).
+type PllSignature = ([EdgeCubelet; 4], [CornerCubelet; 4]);
+
+fn oll_signature(permutation: &CubePermutation3) -> OllSignature {
+    (
+        [UR, UF, UL, UB].map(|edge| {
+            permutation
+                .edges()
+                .unact(&(EdgeCubelet(edge), CyclicGroup::identity()))
+                .1
+        }),
+        [FUL, FUR, BUR, BUL].map(|corner| {
+            permutation
+                .corners()
+                .unact(&(CornerCubelet(corner), CyclicGroup::identity()))
+                .1
+        }),
+    )
+}
+
+fn pll_signature(permutation: &CubePermutation3) -> PllSignature {
+    (
+        [UR, UF, UL, UB].map(|edge| {
+            permutation
+                .edges()
+                .act(&(EdgeCubelet(edge), CyclicGroup::identity()))
+                .0
+        }),
+        [FUL, FUR, BUR, BUL].map(|corner| {
+            permutation
+                .corners()
+                .act(&(CornerCubelet(corner), CyclicGroup::identity()))
+                .0
+        }),
+    )
+}
+
+/// Checks that the centres and the first two layers are solved.
+fn first_two_layers_solved(permutation: &CubePermutation3) -> bool {
+    permutation.centres() == &Unital::identity()
+        && [DR, DF, DL, DB, FR, FL, BR, BL].into_iter().all(|edge| {
+            permutation
+                .edges()
+                .act(&(EdgeCubelet(edge), CyclicGroup::identity()))
+                == (EdgeCubelet(edge), CyclicGroup::identity())
+        })
+        && [FDR, FDL, BDR, BDL].into_iter().all(|corner| {
+            permutation
+                .corners()
+                .act(&(CornerCubelet(corner), CyclicGroup::identity()))
+                == (CornerCubelet(corner), CyclicGroup::identity())
+        })
+}
+
+/// Every state reachable by performing the inverse of the algorithm, with any AUF before and after.
+fn case_states(alg: MoveSequence) -> Vec<CubePermutation3> {
+    let aufs = ["U", "U2", "U'"]
+        .into_iter()
+        .map(|x| x.parse::<MoveSequence>().unwrap())
+        .chain(std::iter::once(MoveSequence { moves: Vec::new() }))
+        .collect::<Vec<_>>();
+    let setup = alg.inverse();
+
+    let mut states = Vec::new();
+    for pre in &aufs {
+        for post in &aufs {
+            let moves = pre
+                .moves
+                .iter()
+                .chain(&setup.moves)
+                .chain(&post.moves)
+                .cloned()
+                .collect();
+            states.push(CubePermutation3::from_move_sequence(MoveSequence { moves }));
+        }
+    }
+    states
+}
+
+lazy_static::lazy_static! {
+    static ref OLL_CASES: HashMap<OllSignature, OllCase> = {
+        let mut cases = HashMap::new();
+        for case in OllCase::enumerate() {
+            for state in case_states(case.alg()) {
+                cases.insert(oll_signature(&state), case);
+            }
+        }
+        cases
+    };
+
+    static ref PLL_CASES: HashMap<PllSignature, PllCase> = {
+        let mut cases = HashMap::new();
+        for case in PllCase::enumerate() {
+            for state in case_states(case.alg()) {
+                cases.insert(pll_signature(&state), case);
+            }
+        }
+        cases
+    };
+}
+
+/// Works out which OLL case the last layer is in, assuming the first two layers are solved.
+/// Returns None if the last layer is already oriented.
+pub fn recognise_oll(cube: &Cube3) -> Option<OllCase> {
+    let permutation = CubePermutation3::from_cube(cube)?;
+    if !first_two_layers_solved(&permutation) {
+        return None;
+    }
+    OLL_CASES.get(&oll_signature(&permutation)).copied()
+}
+
+/// Works out which PLL case the last layer is in, assuming the rest of the cube is solved
+/// and the last layer is oriented.
+/// Also returns the turn of the U face (if any) that lines the case up with its algorithm,
+/// so that turning U and then performing the algorithm solves the cube up to a final AUF.
+/// Where several turns would do, as in symmetric cases such as the H permutation, the smallest is chosen.
+/// Returns None if the last layer is solved up to an AUF.
+pub fn recognise_pll(cube: &Cube3) -> Option<(PllCase, Option<RotationType>)> {
+    let permutation = CubePermutation3::from_cube(cube)?;
+    if !first_two_layers_solved(&permutation)
+        || oll_signature(&permutation) != oll_signature(&CubePermutation3::identity())
+    {
+        return None;
+    }
+    let case = *PLL_CASES.get(&pll_signature(&permutation))?;
+
+    let auf = |auf: Option<RotationType>| {
+        auf.map_or(CubePermutation3::identity(), |rotation_type| {
+            CubePermutation3::from_move(Move::new(Axis::UD, rotation_type, 0, 1))
+        })
+    };
+    let aufs = [
+        None,
+        Some(RotationType::Normal),
+        Some(RotationType::Double),
+        Some(RotationType::Inverse),
+    ];
+    let alg = CubePermutation3::from_move_sequence(case.alg());
+    let pre_auf = aufs
+        .into_iter()
+        .find(|&pre| {
+            let after = alg.op(auf(pre).op(permutation));
+            aufs.into_iter().any(|post| auf(post) == after)
+        })
+        .expect("some AUF lines up every case with its algorithm");
+    Some((case, pre_auf))
+}
+
+/// Works out which OLL case the last layer is in, or if it is oriented, which PLL case it is in.
+pub fn recognise_last_layer(cube: &Cube3) -> Option<LastLayerCase> {
+    recognise_oll(cube)
+        .map(LastLayerCase::Oll)
+        .or_else(|| recognise_pll(cube).map(|(case, _)| LastLayerCase::Pll(case)))
+}
+
+/// Every OLL and PLL case, paired with a setup scramble that produces the case from a solved cube.
+/// The setup scramble is the inverse of the case's algorithm.
+pub fn last_layer_deck() -> Vec<(LastLayerCase, MoveSequence)> {
+    OllCase::enumerate()
+        .into_iter()
+        .map(LastLayerCase::Oll)
+        .chain(PllCase::enumerate().into_iter().map(LastLayerCase::Pll))
+        .map(|case| (case, case.alg().inverse()))
+        .collect()
+}
+
+/// A scramble that produces the given OLL case from a solved cube, such that it is solved by
+/// turning the U face by `auf` (if any) and then performing the case's algorithm.
+pub fn setup_to_case(case: OllCase, auf: Option<RotationType>) -> Vec<Move> {
+    let mut setup = case.alg().inverse().moves;
+    if let Some(auf) = auf {
+        setup.push(Move::new(Axis::UD, auf.inverse(), 0, 1));
+    }
+    setup
+}
+
+lazy_static::lazy_static! {
+    /// Well known algorithms by name. Every PLL is included, named like `T-Perm`.
+    static ref ALGS: HashMap<String, MoveSequence> = {
+        let mut algs = [
+            ("Sune", "R U R' U R U2 R'"),
+            ("Antisune", "R U2 R' U' R U' R'"),
+            ("Sexy", "R U R' U'"),
+            ("Sledgehammer", "R' F R F'"),
+            ("Niklas", "R U' L' U R' U' L"),
+        ]
+        .into_iter()
+        .map(|(name, alg)| (name.to_string(), alg.parse().unwrap()))
+        .collect::<HashMap<_, _>>();
+        for case in PllCase::enumerate() {
+            algs.insert(format!("{:?}-Perm", case), case.alg());
+        }
+        algs
+    };
+}
+
+/// Looks up a well known algorithm by name, such as `Sune` or `Jb-Perm`.
+pub fn alg_by_name(name: &str) -> Option<Vec<Move>> {
+    ALGS.get(name).map(|alg| alg.moves.clone())
+}
+
+impl Cube3 {
+    /// Which of the eight U face stickers around the centre are the colour of the U centre.
+    /// Bit 0 is the back left sticker, and the bits go clockwise around the U face as seen from above,
+    /// so even bits are corners and odd bits are edges.
+    pub fn ll_orientation_mask(&self) -> u16 {
+        let u = self.face(FaceType::U);
+        [
+            (0, 0),
+            (0, 1),
+            (0, 2),
+            (1, 2),
+            (2, 2),
+            (2, 1),
+            (2, 0),
+            (1, 0),
+        ]
+        .into_iter()
+        .enumerate()
+        .filter(|&(_, idx)| u[idx] == u[(1, 1)])
+        .fold(0, |mask, (bit, _)| mask | 1 << bit)
+    }
+
+    /// The stickers of the last layer: the U face, then the top rows of the F, R, B and L faces.
+    fn ll_stickers(&self) -> Vec<Colour> {
+        let u = self.face(FaceType::U);
+        (0..3)
+            .flat_map(|i| (0..3).map(move |j| u[(i, j)]))
+            .chain(
+                [FaceType::F, FaceType::R, FaceType::B, FaceType::L]
+                    .into_iter()
+                    .flat_map(|face| (0..3).map(move |j| self.face(face)[(0, j)])),
+            )
+            .collect()
+    }
+
+    /// Whether the last layers of two cubes look the same, up to a turn of the U face.
+    /// Only the U face and the top row of each side face are compared, so the rest of the cubes may differ.
+    pub fn ll_equal(&self, other: &Cube3) -> bool {
+        let stickers = self.ll_stickers();
+        let u = "U".parse().unwrap();
+        let mut other = other.clone();
+        for _ in 0..4 {
+            if other.ll_stickers() == stickers {
+                return true;
+            }
+            other = other.perform(u);
+        }
+        false
+    }
+}
+
+/// Encodes a last layer state as a number, one digit for the orientation and then the position of each
+/// last layer piece. Different states always have different codes.
+fn state_code(permutation: &CubePermutation3) -> u64 {
+    let (edge_orientations, corner_orientations) = oll_signature(permutation);
+    let (edges, corners) = pll_signature(permutation);
+    edge_orientations
+        .map(|orientation| (orientation.get_value(), 2))
+        .into_iter()
+        .chain(corner_orientations.map(|orientation| (orientation.get_value(), 3)))
+        .chain(edges.map(|edge| (edge.0 as u8, 12)))
+        .chain(corners.map(|corner| (corner.0 as u8, 8)))
+        .fold(0, |code, (digit, base)| code * base + digit as u64)
+}
+
+/// A number identifying the last layer case that an algorithm solves: the smallest code of any of its states.
+/// Two algorithms have the same fingerprint exactly when they solve the same case, up to AUFs before and after.
+fn case_fingerprint(alg: &[Move]) -> u64 {
+    case_states(MoveSequence {
+        moves: alg.to_vec(),
+    })
+    .iter()
+    .map(state_code)
+    .min()
+    .unwrap()
+}
+
+/// The fingerprints of the last layer cases solved by a set of algorithms.
+/// Each algorithm is assumed to preserve the first two layers.
+pub fn cases_covered(algs: &[Vec<Move>]) -> HashSet<u64> {
+    algs.iter().map(|alg| case_fingerprint(alg)).collect()
+}
+
+/// The cases in `all_cases` that are not covered.
+pub fn missing_cases(covered: &HashSet<u64>, all_cases: &HashSet<u64>) -> HashSet<u64> {
+    all_cases.difference(covered).copied().collect()
+}
+
+/// Places and orients the last layer corners of a cube whose first two layers are solved,
+/// using the keyhole technique. The last layer edges are left for a later step.
+///
+/// # Panics
+/// Panics if the first two layers are not solved.
+pub fn solve_last_layer_corners_keyhole(cube: Cube3) -> Vec<Move> {
+    CubePermutation3::from_cube(&cube)
+        .and_then(|permutation| KEYHOLE_CORNERS.solve(&ll_corner_signature(&permutation)))
+        .expect("the first two layers must be solved")
+        .moves
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::cube::Cube;
+
+    use super::*;
+
+    #[test]
+    fn algs_preserve_first_two_layers() {
+        let mut oll_signatures = HashSet::new();
+        for case in OllCase::enumerate() {
+            let permutation = CubePermutation3::from_move_sequence(case.alg());
+            assert!(first_two_layers_solved(&permutation), "{}", case);
+            // Every OLL algorithm must orient a different case.
+            assert!(
+                oll_signatures.insert(oll_signature(&permutation.inverse())),
+                "{}",
+                case
+            );
+        }
+        for case in PllCase::enumerate() {
+            let permutation = CubePermutation3::from_move_sequence(case.alg());
+            assert!(first_two_layers_solved(&permutation), "{}", case);
+            assert_eq!(
+                oll_signature(&permutation),
+                oll_signature(&CubePermutation3::identity()),
+                "{}",
+                case
+            );
+        }
+        // No two cases share a state, even up to AUF.
+        for case in PllCase::enumerate() {
+            for state in case_states(case.alg()) {
+                assert_eq!(PLL_CASES[&pll_signature(&state)], case);
+            }
+        }
+    }
+
+    #[test]
+    fn orientation_mask() {
+        assert_eq!(Cube::<3>::new().ll_orientation_mask(), 0xff);
+        // All edges are oriented, but only the back right corner.
+        let cube = "R U2 R' U' R U' R'"
+            .parse::<MoveSequence>()
+            .unwrap()
+            .inverse()
+            .moves
+            .into_iter()
+            .fold(Cube::<3>::new(), |cube, mv| cube.perform(mv));
+        assert_eq!(cube.ll_orientation_mask(), 0b1010_1110);
+        // A dot case has no edges oriented.
+        let cube = OllCase::new(3)
+            .unwrap()
+            .alg()
+            .inverse()
+            .moves
+            .into_iter()
+            .fold(Cube::<3>::new(), |cube, mv| cube.perform(mv));
+        assert_eq!(cube.ll_orientation_mask() & 0b1010_1010, 0);
+    }
+
+    #[test]
+    fn deck_recognition() {
+        let deck = last_layer_deck();
+        assert_eq!(deck.len(), 57 + 21);
+        for (case, setup) in deck {
+            let cube = setup
+                .moves
+                .into_iter()
+                .fold(Cube::<3>::new(), |cube, mv| cube.perform(mv));
+            assert_eq!(recognise_last_layer(&cube), Some(case));
+        }
+        assert_eq!(recognise_last_layer(&Cube::new()), None);
+    }
+
+    #[test]
+    fn keyhole_corners() {
+        // Every OLL and PLL case, and some combinations of the two, keeping the first two layers intact.
+        let deck = last_layer_deck();
+        let scrambles = deck.iter().map(|(_, setup)| setup.clone()).chain(
+            deck[..57]
+                .iter()
+                .zip(deck[57..].iter().cycle())
+                .map(|((_, oll), (_, pll))| MoveSequence {
+                    moves: pll.moves.iter().chain(&oll.moves).copied().collect(),
+                }),
+        );
+        for scramble in scrambles {
+            let cube = Cube::<3>::new().perform_all(scramble.moves.iter().copied());
+            let solution = solve_last_layer_corners_keyhole(cube);
+            // The keyhole is the only slot that is broken along the way.
+            for mv in &solution {
+                assert!(matches!(
+                    mv.to_string().as_str(),
+                    "U" | "U2" | "U'" | "R" | "R'" | "D" | "D2" | "D'"
+                ));
+            }
+            let permutation =
+                CubePermutation3::from_move_sequence(MoveSequence { moves: solution })
+                    .op(CubePermutation3::from_move_sequence(scramble.clone()));
+
+            // Every corner is now solved.
+            for corner in CornerCubelet::enumerate() {
+                assert_eq!(
+                    permutation
+                        .corners()
+                        .act(&(corner, CyclicGroup::identity())),
+                    (corner, CyclicGroup::identity()),
+                    "{}",
+                    scramble
+                );
+            }
+            // The first two layers are still solved, but the last layer edges may not be.
+            assert!(first_two_layers_solved(&permutation), "{}", scramble);
+        }
+    }
+
+    #[test]
+    fn pll_indices() {
+        for (i, case) in PllCase::enumerate().into_iter().enumerate() {
+            assert_eq!(case.index(), i);
+            assert_eq!(PllCase::from_index(i), case);
+        }
+    }
+
+    #[test]
+    fn coverage() {
+        let algs = PllCase::enumerate()
+            .into_iter()
+            .map(|case| case.alg().moves)
+            .collect::<Vec<_>>();
+        let all_cases = cases_covered(&algs);
+        assert_eq!(all_cases.len(), 21);
+        // A different algorithm for the T-Perm, starting from another angle, covers the same case.
+        assert!(all_cases.contains(&case_fingerprint(
+            &"U R U R' U' R' F R2 U' R' U' R U R' F'"
+                .parse::<MoveSequence>()
+                .unwrap()
+                .moves
+        )));
+
+        let covered = cases_covered(&algs[1..]);
+        let missing = missing_cases(&covered, &all_cases);
+        assert_eq!(missing.len(), 1);
+        assert!(missing.contains(&case_fingerprint(&algs[0])));
+    }
+
+    #[test]
+    fn fingerprints() {
+        // Fingerprints do not depend on hashing, so they can be stored and compared between runs.
+        assert_eq!(case_fingerprint(&[]), 700965);
+        assert_eq!(case_fingerprint(&PllCase::T.alg().moves), 1871973);
+        // Every OLL and PLL case has its own fingerprint.
+        let algs = last_layer_deck()
+            .into_iter()
+            .map(|(_, setup)| setup.inverse().moves)
+            .collect::<Vec<_>>();
+        assert_eq!(cases_covered(&algs).len(), 57 + 21);
+    }
+
+    #[test]
+    fn algs_by_name() {
+        let sune = alg_by_name("Sune").unwrap();
+        assert_eq!(sune.len(), 7);
+        assert_eq!(MoveSequence { moves: sune }.to_string(), "R U R' U R U2 R'");
+        assert_eq!(alg_by_name("Jb-Perm"), Some(PllCase::Jb.alg().moves));
+        assert_eq!(alg_by_name("T-Perm"), Some(PllCase::T.alg().moves));
+        assert_eq!(alg_by_name("Q-Perm"), None);
+        assert_eq!(alg_by_name("sune"), None);
+    }
+
+    #[test]
+    fn ll_equal() {
+        let setup = |case: LastLayerCase, auf: &str| {
+            case.alg()
+                .inverse()
+                .moves
+                .into_iter()
+                .chain(auf.split_whitespace().map(|mv| mv.parse::<Move>().unwrap()))
+                .fold(Cube::new(), |cube, mv| cube.perform(mv))
+        };
+        let sune = LastLayerCase::Oll(OllCase(27));
+        let t_perm = LastLayerCase::Pll(PllCase::T);
+        for case in [sune, t_perm] {
+            for auf in ["U", "U2", "U'"] {
+                assert!(setup(case, "").ll_equal(&setup(case, auf)));
+                assert!(setup(case, auf).ll_equal(&setup(case, "")));
+            }
+        }
+        assert!(!setup(sune, "").ll_equal(&setup(LastLayerCase::Oll(OllCase(26)), "")));
+        assert!(!setup(t_perm, "").ll_equal(&setup(LastLayerCase::Pll(PllCase::Jb), "U")));
+        assert!(!setup(t_perm, "").ll_equal(&Cube::new()));
+        // Only the last layer is compared.
+        let mut cube = setup(t_perm, "");
+        cube.face_mut(FaceType::D)[(0, 0)] = Colour::White;
+        assert!(cube.ll_equal(&setup(t_perm, "U2")));
+    }
+
+    #[test]
+    fn pll_auf() {
+        let t_perm = PllCase::T.alg().inverse();
+        for (auf, expected) in [
+            ("", None),
+            ("U", Some(RotationType::Inverse)),
+            ("U2", Some(RotationType::Double)),
+            ("U'", Some(RotationType::Normal)),
+        ] {
+            for post in ["", "U", "U2", "U'"] {
+                // Turning the U face before the setup only changes the AUF needed after the algorithm.
+                let cube = format!("{} {} {}", post, t_perm, auf)
+                    .split_whitespace()
+                    .map(|mv| mv.parse::<Move>().unwrap())
+                    .fold(Cube::new(), |cube, mv| cube.perform(mv));
+                assert_eq!(recognise_pll(&cube), Some((PllCase::T, expected)));
+                let aligned = expected
+                    .map(|rotation_type| Move::new(Axis::UD, rotation_type, 0, 1))
+                    .into_iter()
+                    .chain(PllCase::T.alg().moves)
+                    .fold(cube, |cube, mv| cube.perform(mv));
+                assert!(aligned.ll_equal(&Cube::new()));
+            }
+        }
+        assert_eq!(recognise_pll(&Cube::new()), None);
+    }
+
+    #[test]
+    fn setups() {
+        let perform =
+            |cube: Cube3, moves: &[Move]| moves.iter().fold(cube, |cube, &mv| cube.perform(mv));
+        let aufs = [
+            None,
+            Some(RotationType::Normal),
+            Some(RotationType::Double),
+            Some(RotationType::Inverse),
+        ];
+        for case in [OllCase(21), OllCase(27), OllCase(45)] {
+            for auf in aufs {
+                let cube = perform(Cube::new(), &setup_to_case(case, auf));
+                assert_eq!(recognise_oll(&cube), Some(case));
+                // Only the requested AUF lines the case up with its algorithm.
+                for other in aufs {
+                    let auf_moves = other
+                        .map(|rotation_type| vec![Move::new(Axis::UD, rotation_type, 0, 1)])
+                        .unwrap_or_default();
+                    let solved = perform(perform(cube.clone(), &auf_moves), &case.alg().moves);
+                    assert_eq!(
+                        solved == Cube::new(),
+                        other == auf,
+                        "{} {:?} {:?}",
+                        case,
+                        auf,
+                        other
+                    );
+                }
+            }
+        }
+    }
+}
@@ -3,18 +3,38 @@
 #![feature(generic_const_exprs)]
 #![allow(incomplete_features)]
 
-mod cube;
-mod group;
-mod intuitive;
-mod permute;
-mod roux;
-mod solve;
+pub mod block;
+pub mod commutator;
+pub mod cross;
+pub mod cube;
+#[cfg(feature = "async")]
+pub mod drive;
+pub mod ergonomics;
+pub mod f2l;
+pub mod grade;
+pub mod group;
+pub mod heuristic;
+pub mod intuitive;
+pub mod last_layer;
+pub mod notation;
+pub mod optimise;
+pub mod path;
+pub mod permute;
+pub mod piece;
+pub mod render;
+pub mod roux;
+pub mod scramble;
+pub mod solvability;
+pub mod solve;
+pub mod sticker;
+pub mod timing;
 mod utils;
-mod algorithmic;
+pub mod algorithmic;
 
 use wasm_bindgen::prelude::*;
 
 use crate::cube::*;
+use crate::timing::TimedMove;
 
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
 // allocator.
@@ -28,10 +48,130 @@ extern "C" {
 }
 
 #[wasm_bindgen]
-pub struct Universe;
+pub struct Universe {
+    cube: Cube<3>,
+    /// The moves made with `perform_timed`, in order.
+    log: Vec<TimedMove>,
+    /// The moves made with `perform_cancelling`, in order.
+    history: Vec<Move>,
+    /// Whether `perform_cancelling` removes a move from the history when it undoes the last move.
+    cancelling: bool,
+}
 
 #[wasm_bindgen]
 pub fn init() -> Universe {
     utils::set_panic_hook();
-    Universe
+    Universe {
+        cube: Cube::new(),
+        log: Vec::new(),
+        history: Vec::new(),
+        cancelling: true,
+    }
+}
+
+/// Compares two cubes given as facelet strings, failing if either string is invalid.
+#[wasm_bindgen]
+pub fn cubes_equal(a: &str, b: &str) -> Result<bool, JsValue> {
+    let parse = |facelets: &str| {
+        Cube::<3>::from_facelets(facelets).map_err(|e| JsValue::from_str(&e.to_string()))
+    };
+    Ok(parse(a)? == parse(b)?)
+}
+
+/// Reads a single move and performs it on a copy of the cube,
+/// explaining why if it cannot be read or turns layers the cube does not have.
+fn perform_token(cube: &Cube<3>, token: &str) -> Result<(Move, Cube<3>), JsValue> {
+    Move::parse_sized(token, 3)
+        .and_then(|mv| Ok((mv, cube.clone().try_perform(mv)?)))
+        .map_err(|error| {
+            let error = AlgorithmError {
+                token: token.to_string(),
+                error,
+            };
+            JsValue::from_str(&error.to_string())
+        })
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Applies each algorithm in turn.
+    /// The algorithms are applied to a copy of the cube first, so if one is invalid,
+    /// an error naming it and the position of its first bad move is returned and the cube is left untouched.
+    pub fn apply_many(&mut self, algs: Vec<String>) -> Result<(), JsValue> {
+        let mut cube = self.cube.clone();
+        for (i, alg) in algs.iter().enumerate() {
+            let invalid = |error: &dyn std::fmt::Display| {
+                JsValue::from_str(&format!("algorithm {} is invalid: {}", i, error))
+            };
+            let sequence = alg.parse::<MoveSequence>().map_err(|error| invalid(&error))?;
+            for mv in sequence.moves {
+                cube = cube.try_perform(mv).map_err(|error| invalid(&error))?;
+            }
+        }
+        self.cube = cube;
+        Ok(())
+    }
+
+    /// Performs a single move made at the given time, recording it for later analysis.
+    pub fn perform_timed(&mut self, token: &str, at_ms: f64) -> Result<(), JsValue> {
+        let (mv, cube) = perform_token(&self.cube, token)?;
+        self.cube = cube;
+        self.log.push(TimedMove { mv, at_ms });
+        Ok(())
+    }
+
+    /// The number of timed moves made per second, from the first to the last.
+    pub fn tps(&self) -> f64 {
+        timing::tps(&self.log)
+    }
+
+    /// The longest time between two consecutive timed moves, in milliseconds.
+    pub fn longest_pause_ms(&self) -> f64 {
+        timing::longest_pause_ms(&self.log)
+    }
+
+    /// Performs a single move, recording it in the history.
+    /// If cancelling is enabled and the move undoes the last move in the history,
+    /// the last move is removed from the history instead.
+    pub fn perform_cancelling(&mut self, token: &str) -> Result<(), JsValue> {
+        let (mv, cube) = perform_token(&self.cube, token)?;
+        self.cube = cube;
+        if self.cancelling && self.history.last() == Some(&mv.inverse()) {
+            self.history.pop();
+        } else {
+            self.history.push(mv);
+        }
+        Ok(())
+    }
+
+    pub fn set_cancelling(&mut self, cancelling: bool) {
+        self.cancelling = cancelling;
+    }
+
+    /// The moves made with `perform_cancelling`, as a space-separated string.
+    pub fn history(&self) -> String {
+        MoveSequence {
+            moves: self.history.clone(),
+        }
+        .to_string()
+    }
+
+    /// A scramble that, applied to a solved cube, reproduces the current state of the cube.
+    /// This is the inverse of a solution to the current state, so it need not be the moves that were made.
+    /// Fails if the solver cannot find a solution.
+    pub fn scramble_to_current(&self) -> Result<String, JsValue> {
+        let solution = solve::solve_from_facelets(&self.cube.to_facelets())
+            .map_err(|error| JsValue::from_str(&error.to_string()))?;
+        let solution =
+            parse_algorithm(&solution).expect("solutions are written in valid notation");
+        Ok(MoveSequence {
+            moves: invert_sequence(&solution),
+        }
+        .to_string())
+    }
+
+    /// Renders the current state of the cube as a net.
+    pub fn net(&self) -> String {
+        self.cube.to_string()
+    }
 }
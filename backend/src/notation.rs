@@ -0,0 +1,37 @@
+use crate::cube::{parse_algorithm, Move, ParseMoveError};
+
+/// Why a sequence of moves written in Singmaster notation could not be read,
+/// including the byte offset of the first move that was not understood.
+pub type ParseError = ParseMoveError;
+
+/// Reads a sequence of moves written in Singmaster notation, such as `"R U R' U' F2 Dw"`.
+/// Face turns, wide turns (`r` or `Rw`), slice moves and rotations may be separated by any whitespace.
+pub fn parse_moves(s: &str) -> Result<Vec<Move>, ParseError> {
+    parse_algorithm(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cube::{Move, MoveSequence};
+
+    #[test]
+    fn parse_moves() {
+        assert_eq!(
+            super::parse_moves(" R U R' U'\tF2\n\nDw  r M E2 S' "),
+            Ok("R U R' U' F2 Dw Rw M E2 S'"
+                .parse::<MoveSequence>()
+                .unwrap()
+                .moves)
+        );
+        assert_eq!(super::parse_moves("r'"), super::parse_moves("Rw'"));
+        assert_eq!(super::parse_moves(""), Ok(Vec::<Move>::new()));
+        assert_eq!(super::parse_moves(" \t\n"), Ok(Vec::new()));
+
+        for (input, token, position) in [("R3", "R3", 0), ("R U  Q", "Q", 5), ("F\tR3 Q", "R3", 2)]
+        {
+            let error = super::parse_moves(input).unwrap_err();
+            assert_eq!((error.token.as_str(), error.position), (token, position));
+            assert!(error.to_string().contains(token));
+        }
+    }
+}
@@ -0,0 +1,410 @@
+use crate::{
+    cube::{Axis, Colour, FaceType, Move, RotationType},
+    group::Enumerable,
+    solve::{Action, ActionReason, ActionSteps},
+};
+
+/// Rewrites runs of moves on the same axis of a 3x3x3 cube with slice moves and whole cube rotations,
+/// where that takes fewer moves. For example, `R L' x'` becomes `M`, and `r R'` becomes `M'`.
+/// The result has exactly the same effect on the cube.
+pub fn prefer_slices(moves: &[Move]) -> Vec<Move> {
+    let mut result = Vec::new();
+    let mut start = 0;
+    while start < moves.len() {
+        let mut end = start + 1;
+        while end < moves.len() && moves[end].axis == moves[start].axis {
+            end += 1;
+        }
+        let run = &moves[start..end];
+        let rewritten = with_slices(run);
+        if rewritten.len() < run.len() {
+            result.extend(rewritten);
+        } else {
+            result.extend_from_slice(run);
+        }
+        start = end;
+    }
+    result
+}
+
+/// The shortest way to write a run of moves on a single axis as a whole cube rotation
+/// followed by turns of individual layers.
+fn with_slices(run: &[Move]) -> Vec<Move> {
+    let axis = run[0].axis;
+    let mut turns = [0; 3];
+    for mv in run {
+        for layer in &mut turns[mv.start_depth..mv.end_depth] {
+            *layer += mv.rotation_type.rotations();
+        }
+    }
+
+    (0..4)
+        .map(|rotation| {
+            let mut moves = Vec::new();
+            if let Some(rotation_type) = RotationType::from_rotations(rotation) {
+                moves.push(Move::new(axis, rotation_type, 0, 3));
+            }
+            for (layer, &layer_turns) in turns.iter().enumerate() {
+                if let Some(rotation_type) = RotationType::from_rotations(layer_turns - rotation) {
+                    moves.push(Move::new(axis, rotation_type, layer, layer + 1));
+                }
+            }
+            moves
+        })
+        .min_by_key(Vec::len)
+        .unwrap()
+}
+
+/// For each position on the cube, the face that was there before some whole cube rotations.
+type Frame = [FaceType; 6];
+
+/// The face that the depths of moves on this axis are measured from.
+fn axis_face(axis: Axis) -> FaceType {
+    match axis {
+        Axis::FB => FaceType::F,
+        Axis::RL => FaceType::R,
+        Axis::UD => FaceType::U,
+    }
+}
+
+/// Where a clockwise quarter turn of the whole cube about this axis moves each face.
+fn quarter_turn(axis: Axis, face: FaceType) -> FaceType {
+    use FaceType::*;
+    let cycle = match axis {
+        Axis::FB => [U, R, D, L],
+        Axis::RL => [F, U, B, D],
+        Axis::UD => [F, L, B, R],
+    };
+    cycle
+        .iter()
+        .position(|&f| f == face)
+        .map_or(face, |i| cycle[(i + 1) % 4])
+}
+
+/// Updates the frame after a whole cube rotation.
+fn rotate_frame(frame: &Frame, rotation: Move) -> Frame {
+    let mut frame = *frame;
+    for _ in 0..rotation.rotation_type.rotations().rem_euclid(4) {
+        let mut rotated = frame;
+        for face in FaceType::enumerate() {
+            rotated[quarter_turn(rotation.axis, face) as usize] = frame[face as usize];
+        }
+        frame = rotated;
+    }
+    frame
+}
+
+/// Writes a move made after the rotations in the frame as the same move made before them.
+fn relabel(frame: &Frame, mv: Move) -> Move {
+    let face = frame[axis_face(mv.axis) as usize];
+    let axis = match face {
+        FaceType::F | FaceType::B => Axis::FB,
+        FaceType::R | FaceType::L => Axis::RL,
+        FaceType::U | FaceType::D => Axis::UD,
+    };
+    if face == axis_face(axis) {
+        Move::new(axis, mv.rotation_type, mv.start_depth, mv.end_depth)
+    } else {
+        Move::new(
+            axis,
+            mv.rotation_type.inverse(),
+            3 - mv.end_depth,
+            3 - mv.start_depth,
+        )
+    }
+}
+
+/// The frame after performing some whole cube rotations, starting in the standard orientation.
+fn frame_after(rotation: &[Move]) -> Frame {
+    rotation
+        .iter()
+        .fold(FaceType::enumerate(), |frame, &mv| rotate_frame(&frame, mv))
+}
+
+/// Each of the 24 orientations of the cube, as the whole cube rotations that reach it from the standard orientation.
+fn all_rotations() -> impl Iterator<Item = Vec<Move>> {
+    // First bring the right face to the top, then turn it about the vertical axis.
+    ["", "x", "x2", "x'", "z", "z'"]
+        .into_iter()
+        .flat_map(|first| {
+            ["", "y", "y2", "y'"].into_iter().map(move |second| {
+                [first, second]
+                    .into_iter()
+                    .filter(|s| !s.is_empty())
+                    .map(|s| s.parse::<Move>().unwrap())
+                    .collect::<Vec<_>>()
+            })
+        })
+}
+
+/// A way of holding the cube, given by the colours of the centres on the U and F faces.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Orientation {
+    up: Colour,
+    front: Colour,
+}
+
+impl Default for Orientation {
+    /// The standard orientation, with white on top and green in front.
+    fn default() -> Self {
+        Self {
+            up: FaceType::U.solved_colour(),
+            front: FaceType::F.solved_colour(),
+        }
+    }
+}
+
+impl Orientation {
+    /// Returns None if the two centres are not adjacent, so the cube cannot be held this way.
+    pub fn new(up: Colour, front: Colour) -> Option<Self> {
+        if up == front || up.home_face().is_opposite(front.home_face()) {
+            None
+        } else {
+            Some(Self { up, front })
+        }
+    }
+
+    pub fn up(self) -> Colour {
+        self.up
+    }
+
+    pub fn front(self) -> Colour {
+        self.front
+    }
+
+    /// The whole cube rotations that bring a cube from the standard orientation to this one.
+    fn rotation(self) -> Vec<Move> {
+        all_rotations()
+            .find(|rotation| {
+                let frame = frame_after(rotation);
+                frame[FaceType::U as usize] == self.up.home_face()
+                    && frame[FaceType::F as usize] == self.front.home_face()
+            })
+            .unwrap()
+    }
+}
+
+/// Rewrites a solution for a 3x3x3 cube held in the orientation `from`,
+/// so that it solves the same cube when held in the orientation `to` instead.
+/// Any whole cube rotations in the solution are combined into the fewest needed, at its end.
+pub fn reorient_solution(moves: &[Move], from: Orientation, to: Orientation) -> Vec<Move> {
+    let inverse = |rotation: Vec<Move>| rotation.into_iter().rev().map(Move::inverse);
+    // Turn the cube back from `to` to `from`, perform the solution, then return to `to`.
+    let conjugated = inverse(to.rotation())
+        .chain(from.rotation())
+        .chain(moves.iter().copied())
+        .chain(inverse(from.rotation()))
+        .chain(to.rotation())
+        .collect::<Vec<_>>();
+    let mut result = remove_rotations(&conjugated);
+    result.extend(net_rotation(&conjugated));
+    result
+}
+
+/// The fewest whole cube rotations with the same effect as all of the rotations in a sequence of moves.
+fn net_rotation(moves: &[Move]) -> Vec<Move> {
+    fewest_rotations(&frame_after(
+        &moves
+            .iter()
+            .copied()
+            .filter(is_rotation)
+            .collect::<Vec<_>>(),
+    ))
+}
+
+/// The fewest whole cube rotations that reach the given frame.
+fn fewest_rotations(frame: &Frame) -> Vec<Move> {
+    all_rotations()
+        .filter(|rotation| frame_after(rotation) == *frame)
+        .min_by_key(Vec::len)
+        .unwrap()
+}
+
+fn is_rotation(mv: &Move) -> bool {
+    (mv.start_depth, mv.end_depth) == (0, 3)
+}
+
+/// Removes the whole cube rotations from a sequence of moves on a 3x3x3 cube, relabelling the moves after each rotation.
+/// The result has the same effect as the original, except that the cube is not rotated at the end.
+pub fn remove_rotations(moves: &[Move]) -> Vec<Move> {
+    let mut frame = FaceType::enumerate();
+    moves
+        .iter()
+        .filter_map(|mv| {
+            if is_rotation(mv) {
+                frame = rotate_frame(&frame, *mv);
+                None
+            } else {
+                Some(relabel(&frame, *mv))
+            }
+        })
+        .collect()
+}
+
+/// Rewrites a solution to a 3x3x3 cube so that it contains as few whole cube rotations as possible.
+/// Each rotation is pushed to the end of the solution by relabelling the moves after it,
+/// and the rotations are then combined into the fewest that have the same effect, if any are needed at all.
+/// The structure of the actions is kept, and the result leaves the cube in exactly the same state.
+pub fn minimize_rotations(actions: &[Action]) -> Vec<Action> {
+    let mut frame = FaceType::enumerate();
+    let mut result = actions
+        .iter()
+        .filter_map(|action| without_rotations(action, &mut frame))
+        .collect::<Vec<_>>();
+
+    let rotation = fewest_rotations(&frame);
+    if !rotation.is_empty() {
+        result.push(Action {
+            reason: ActionReason::Intuitive,
+            description: Some("Rotation".to_string()),
+            steps: ActionSteps::Sequence {
+                actions: rotation
+                    .into_iter()
+                    .map(|mv| Action {
+                        reason: ActionReason::Intuitive,
+                        description: None,
+                        steps: ActionSteps::Move { mv },
+                    })
+                    .collect(),
+            },
+        });
+    }
+    result
+}
+
+/// Relabels the moves of an action by the rotations made so far, and adds its own rotations to the frame.
+/// Returns None if the action is a single rotation.
+fn without_rotations(action: &Action, frame: &mut Frame) -> Option<Action> {
+    let steps = match &action.steps {
+        ActionSteps::Move { mv } if is_rotation(mv) => {
+            *frame = rotate_frame(frame, *mv);
+            return None;
+        }
+        ActionSteps::Move { mv } => ActionSteps::Move {
+            mv: relabel(frame, *mv),
+        },
+        ActionSteps::Sequence { actions } => ActionSteps::Sequence {
+            actions: actions
+                .iter()
+                .filter_map(|action| without_rotations(action, frame))
+                .collect(),
+        },
+    };
+    Some(Action {
+        reason: action.reason.clone(),
+        description: action.description.clone(),
+        steps,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        cube::{Cube, MoveSequence},
+        solve::move_sequence_to_intuitive_action,
+    };
+
+    use super::*;
+
+    fn moves(alg: &str) -> Vec<Move> {
+        alg.parse::<MoveSequence>().unwrap().moves
+    }
+
+    fn perform(moves: &[Move]) -> Cube<3> {
+        // Start from a scrambled cube, so that every sticker is checked.
+        "R U2 F' L D B2"
+            .parse::<MoveSequence>()
+            .unwrap()
+            .moves
+            .iter()
+            .chain(moves)
+            .fold(Cube::new(), |cube, &mv| cube.perform(mv))
+    }
+
+    #[test]
+    fn slices() {
+        for (input, expected) in [
+            ("R L' x'", "M"),
+            ("r R'", "M'"),
+            ("U D' y'", "E"),
+            ("F' B z", "S"),
+            ("R U R' r R' U'", "R U R' M' U'"),
+            // Already as short as it can be.
+            ("R L'", "R L'"),
+        ] {
+            let rewritten = prefer_slices(&moves(input));
+            assert_eq!(
+                MoveSequence {
+                    moves: rewritten.clone()
+                }
+                .to_string(),
+                expected
+            );
+            assert_eq!(perform(&rewritten), perform(&moves(input)));
+        }
+    }
+
+    #[test]
+    fn reorient() {
+        let standard = Orientation::default();
+        let yellow_up = Orientation::new(Colour::Yellow, Colour::Green).unwrap();
+        assert_eq!(
+            reorient_solution(&moves("R U F' M"), standard, yellow_up),
+            moves("L D F' M'")
+        );
+        assert_eq!(Orientation::new(Colour::White, Colour::Yellow), None);
+
+        let scramble = moves("R U2 F' L D B2 R' U M2 x");
+        let solution = moves("x' M2 U' R B2 D' L' F U2 R'");
+        let from = Orientation::new(Colour::Orange, Colour::Blue).unwrap();
+        let to = Orientation::new(Colour::Green, Colour::Red).unwrap();
+        // Scramble the cube, then hold it in each orientation.
+        let held = |orientation: Orientation| {
+            scramble
+                .iter()
+                .chain(&orientation.rotation())
+                .fold(Cube::<3>::new(), |cube, &mv| cube.perform(mv))
+        };
+        let from_solution = reorient_solution(&solution, standard, from);
+        let to_solution = reorient_solution(&from_solution, from, to);
+        for (orientation, solution) in [(from, from_solution), (to, to_solution)] {
+            let solved = solution
+                .iter()
+                .fold(held(orientation), |cube, &mv| cube.perform(mv));
+            assert_eq!(solved.face(FaceType::U)[(1, 1)], orientation.up());
+            assert_eq!(solved.face(FaceType::F)[(1, 1)], orientation.front());
+            assert!(FaceType::enumerate()
+                .into_iter()
+                .all(|face| solved.is_face_solved(face)));
+        }
+    }
+
+    #[test]
+    fn rotations() {
+        for (input, expected) in [
+            ("y R U R' y' U'", "B U B' U'"),
+            ("x2 R U2 M' x2", "R D2 M'"),
+            ("R y F z", "R R x y"),
+            ("y2 R U", "L U y2"),
+        ] {
+            let actions = [move_sequence_to_intuitive_action(
+                "Test",
+                MoveSequence {
+                    moves: moves(input),
+                },
+            )];
+            let rewritten = minimize_rotations(&actions)
+                .iter()
+                .flat_map(|action| action.steps.move_sequence().moves)
+                .collect::<Vec<_>>();
+            assert_eq!(
+                MoveSequence {
+                    moves: rewritten.clone()
+                }
+                .to_string(),
+                expected
+            );
+            assert_eq!(perform(&rewritten), perform(&moves(input)));
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use crate::{
+    cube::{all_basic_moves, Cube, Move},
+    group::{InverseSemigroup, Magma},
+    permute::CubePermutation3,
+};
+
+/// The longest path `shortest_path` searches for before giving up.
+/// Each side of the search reaches about a million states at this depth.
+pub const MAX_DEPTH: usize = 10;
+
+/// Extends one side of the search by a move, returning the newly reached states.
+/// Forwards, `paths` holds the moves that reach each state from the start.
+/// Backwards, it holds the moves that reach the target from each state.
+fn extend(
+    layer: &[CubePermutation3],
+    paths: &mut HashMap<CubePermutation3, Vec<Move>>,
+    moves: &[(Move, CubePermutation3)],
+    forwards: bool,
+) -> Vec<CubePermutation3> {
+    let mut next_layer = Vec::new();
+    for state in layer {
+        for &(mv, permutation) in moves {
+            let (next, path) = if forwards {
+                let mut path = paths[state].clone();
+                path.push(mv);
+                (permutation.op(*state), path)
+            } else {
+                let mut path = vec![mv];
+                path.extend(paths[state].iter().copied());
+                (permutation.inverse().op(*state), path)
+            };
+            if let Entry::Vacant(entry) = paths.entry(next) {
+                entry.insert(path);
+                next_layer.push(next);
+            }
+        }
+    }
+    next_layer
+}
+
+/// Finds the fewest outer layer moves (in the half turn metric) that turn `from` into `to`.
+/// This meets in the middle, searching forwards from `from` and backwards from `to`.
+/// Returns None if either cube is not a valid set of pieces, or the states are more than
+/// `MAX_DEPTH` moves apart.
+pub fn shortest_path(from: &Cube<3>, to: &Cube<3>) -> Option<Vec<Move>> {
+    shortest_path_within(from, to, MAX_DEPTH)
+}
+
+/// As `shortest_path`, but gives up on states more than `max_depth` moves apart.
+/// The search takes time and memory exponential in `max_depth`, so it should be at most `MAX_DEPTH`.
+pub fn shortest_path_within(from: &Cube<3>, to: &Cube<3>, max_depth: usize) -> Option<Vec<Move>> {
+    let start = CubePermutation3::from_cube(from)?;
+    let target = CubePermutation3::from_cube(to)?;
+    if start == target {
+        return Some(Vec::new());
+    }
+    let moves = all_basic_moves()
+        .into_iter()
+        .map(|mv| (mv, CubePermutation3::from_move(mv)))
+        .collect::<Vec<_>>();
+
+    let mut forward_paths = HashMap::new();
+    forward_paths.insert(start, Vec::new());
+    let mut backward_paths = HashMap::new();
+    backward_paths.insert(target, Vec::new());
+    let mut forward_layer = vec![start];
+    let mut backward_layer = vec![target];
+
+    // Alternate sides, so the first time the searches meet gives a shortest path.
+    for depth in 1..=max_depth {
+        let forwards = depth % 2 == 1;
+        let (layer, paths, other_paths) = if forwards {
+            (&mut forward_layer, &mut forward_paths, &backward_paths)
+        } else {
+            (&mut backward_layer, &mut backward_paths, &forward_paths)
+        };
+        *layer = extend(layer, paths, &moves, forwards);
+        for state in layer.iter() {
+            if let Some(other_path) = other_paths.get(state) {
+                let (first, second) = if forwards {
+                    (&paths[state], other_path)
+                } else {
+                    (other_path, &paths[state])
+                };
+                return Some(first.iter().chain(second).copied().collect());
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_move_apart() {
+        let from = Cube::new().apply_algorithm("R U F'").unwrap();
+        let to = Cube::new().apply_algorithm("R U F' D2").unwrap();
+        assert_eq!(shortest_path(&from, &to), Some(vec!["D2".parse().unwrap()]));
+        assert_eq!(shortest_path(&from, &from), Some(Vec::new()));
+        assert_eq!(shortest_path_within(&from, &to, 0), None);
+    }
+
+    #[test]
+    fn path_between_states() {
+        let from = Cube::new().apply_algorithm("L2 B U'").unwrap();
+        let to = Cube::new().apply_algorithm("F R' U2 D").unwrap();
+        let path = shortest_path(&from, &to).unwrap();
+        // Undoing the first scramble and then doing the second takes seven moves.
+        assert!(path.len() <= 7);
+        assert_eq!(from.apply_sequence(&path), to);
+    }
+}
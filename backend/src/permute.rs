@@ -4,7 +4,7 @@ use crate::cube::CornerType::*;
 use crate::cube::EdgeType::*;
 use crate::cube::FaceType::*;
 use crate::{
-    cube::{Axis, CornerType, EdgeType, FaceType, Move, MoveSequence, RotationType},
+    cube::{Axis, Colour, CornerType, Cube, EdgeType, FaceType, Move, MoveSequence, RotationType},
     group::*,
 };
 
@@ -105,7 +105,7 @@ pub type CornerPermutation = OrientedSymmetricGroup<CornerCubelet, 3>;
 
 /// Represents a permutation of a 3x3x3 cube.
 /// This is the direct product of a centre permutation, edge permutation, and corner permutation group.
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub struct CubePermutation3 {
     centres: CentrePermutation,
     edges: EdgePermutation,
@@ -429,6 +429,64 @@ impl CornerPermutation {
     }
 }
 
+/// A single sticker on a 3x3x3 cube, given by its face, row and column.
+pub type Facelet = (FaceType, usize, usize);
+
+/// The stickers of an edge position, with the key sticker first.
+pub fn edge_facelets(edge: EdgeType) -> [Facelet; 2] {
+    match edge {
+        UR => [(U, 1, 2), (R, 0, 1)],
+        UF => [(U, 2, 1), (F, 0, 1)],
+        UL => [(U, 1, 0), (L, 0, 1)],
+        UB => [(U, 0, 1), (B, 0, 1)],
+        DR => [(D, 1, 2), (R, 2, 1)],
+        DF => [(D, 0, 1), (F, 2, 1)],
+        DL => [(D, 1, 0), (L, 2, 1)],
+        DB => [(D, 2, 1), (B, 2, 1)],
+        FR => [(F, 1, 2), (R, 1, 0)],
+        FL => [(F, 1, 0), (L, 1, 2)],
+        BR => [(B, 1, 0), (R, 1, 2)],
+        BL => [(B, 1, 2), (L, 1, 0)],
+    }
+}
+
+/// The stickers of a corner position, in clockwise order starting from the U/D sticker.
+pub fn corner_facelets(corner: CornerType) -> [Facelet; 3] {
+    match corner {
+        FUR => [(U, 2, 2), (R, 0, 0), (F, 0, 2)],
+        FUL => [(U, 2, 0), (F, 0, 0), (L, 0, 2)],
+        FDR => [(D, 0, 2), (F, 2, 2), (R, 2, 0)],
+        FDL => [(D, 0, 0), (L, 2, 2), (F, 2, 0)],
+        BUR => [(U, 0, 2), (B, 0, 0), (R, 0, 2)],
+        BUL => [(U, 0, 0), (L, 0, 0), (B, 0, 2)],
+        BDR => [(D, 2, 2), (R, 2, 2), (B, 2, 0)],
+        BDL => [(D, 2, 0), (B, 2, 2), (L, 2, 0)],
+    }
+}
+
+impl Cube<3> {
+    /// The other sticker of the edge piece that the given sticker belongs to, with its location and colour.
+    /// Returns None if the sticker is on a corner or a centre.
+    pub fn edge_partner(
+        &self,
+        face: FaceType,
+        row: usize,
+        col: usize,
+    ) -> Option<(FaceType, usize, usize, Colour)> {
+        EdgeType::enumerate().into_iter().find_map(|edge| {
+            let facelets = edge_facelets(edge);
+            let index = facelets.iter().position(|&f| f == (face, row, col))?;
+            let (partner_face, partner_row, partner_col) = facelets[1 - index];
+            Some((
+                partner_face,
+                partner_row,
+                partner_col,
+                self.face(partner_face)[(partner_row, partner_col)],
+            ))
+        })
+    }
+}
+
 impl Magma for CubePermutation3 {
     fn op(self, other: Self) -> Self {
         Self {
@@ -539,6 +597,67 @@ impl CubePermutation3 {
         g
     }
 
+    /// Reads the permutation off the stickers of a 3x3x3 cube.
+    /// Returns None if the stickers do not describe a set of real pieces,
+    /// for example if two stickers on the same edge have the same colour, or a piece appears twice.
+    pub fn from_cube(cube: &Cube<3>) -> Option<Self> {
+        let colour = |(face, row, col): Facelet| cube.face(face)[(row, col)].home_face();
+
+        let mut centres = FaceType::enumerate();
+        for face in FaceType::enumerate() {
+            centres[colour((face, 1, 1)).index()] = face;
+        }
+
+        let mut edges = EdgeType::enumerate().map(|e| (e, CyclicGroup::identity()));
+        for position in EdgeType::enumerate() {
+            let [key, other] = edge_facelets(position);
+            // The orientation is zero exactly when the piece's key sticker lies on the position's key face.
+            let (piece, orientation) = EdgeType::from_faces(colour(key), colour(other))?;
+            edges[piece.index()] = (position, orientation);
+        }
+
+        let mut corners = CornerType::enumerate().map(|c| (c, CyclicGroup::identity()));
+        for position in CornerType::enumerate() {
+            let facelets = corner_facelets(position);
+            let colours = facelets.map(colour);
+            // The orientation counts the clockwise turns from the U/D facelet to the U/D colour.
+            let orientation = colours.iter().position(|&c| matches!(c, U | D))?;
+            let by_axis = |axis: [FaceType; 2]| colours.into_iter().find(|c| axis.contains(c));
+            let piece = CornerType::from_faces_ordered(
+                by_axis([F, B])?,
+                by_axis([U, D])?,
+                by_axis([R, L])?,
+            )?;
+            corners[piece.index()] = (position, CyclicGroup::new(orientation as u8));
+        }
+
+        // Each piece must have been seen exactly once.
+        let mut seen_centres = [false; 6];
+        let mut seen_edges = [false; 12];
+        let mut seen_corners = [false; 8];
+        for face in centres {
+            seen_centres[face.index()] = true;
+        }
+        for (edge, _) in edges {
+            seen_edges[edge.index()] = true;
+        }
+        for (corner, _) in corners {
+            seen_corners[corner.index()] = true;
+        }
+        if seen_centres.contains(&false)
+            || seen_edges.contains(&false)
+            || seen_corners.contains(&false)
+        {
+            return None;
+        }
+
+        Some(Self {
+            centres: CentrePermutation::new_unchecked(centres.map(CentreCubelet)),
+            edges: EdgePermutation::new_unchecked(edges.map(|(e, r)| (EdgeCubelet(e), r))),
+            corners: CornerPermutation::new_unchecked(corners.map(|(c, r)| (CornerCubelet(c), r))),
+        })
+    }
+
     /// Get a reference to the cube permutation's centres.
     pub fn centres(&self) -> &CentrePermutation {
         &self.centres
@@ -778,4 +897,63 @@ mod tests {
         let g = CubePermutation3::from_move_sequence(superflip);
         assert_eq!(g.order(), 2);
     }
+
+    #[test]
+    fn from_cube() {
+        assert_eq!(
+            CubePermutation3::from_cube(&Cube::new()),
+            Some(CubePermutation3::identity())
+        );
+        for alg in [
+            "F",
+            "R",
+            "U",
+            "B",
+            "L",
+            "D",
+            "M",
+            "E",
+            "S",
+            "R U R' U'",
+            "B R2 U2 F R' U' B2 F U R2 U2 L' D' R2 D L R' F' R F2 B2 U D' R L2",
+            "M' U M2 E S' r u' f2",
+        ] {
+            let seq = alg.parse::<MoveSequence>().unwrap();
+            let cube = seq
+                .moves
+                .iter()
+                .fold(Cube::<3>::new(), |cube, &mv| cube.perform(mv));
+            assert_eq!(
+                CubePermutation3::from_cube(&cube),
+                Some(CubePermutation3::from_move_sequence(seq)),
+                "{}",
+                alg
+            );
+        }
+    }
+
+    #[test]
+    fn edge_partner() {
+        let cube = Cube::new().perform("R".parse().unwrap());
+        assert_eq!(cube.edge_partner(U, 2, 1), Some((F, 0, 1, Colour::Green)));
+        assert_eq!(cube.edge_partner(F, 0, 1), Some((U, 2, 1, Colour::White)));
+        // After R, the UR edge holds the FR edge piece.
+        assert_eq!(cube.edge_partner(U, 1, 2), Some((R, 0, 1, Colour::Red)));
+        assert_eq!(cube.edge_partner(R, 0, 1), Some((U, 1, 2, Colour::Green)));
+        assert_eq!(cube.edge_partner(B, 1, 2), Some((L, 1, 0, Colour::Orange)));
+        assert_eq!(cube.edge_partner(D, 2, 1), Some((B, 2, 1, Colour::Blue)));
+        for (face, row, col) in [(U, 0, 0), (F, 2, 2), (R, 1, 1), (D, 1, 1)] {
+            assert_eq!(cube.edge_partner(face, row, col), None);
+        }
+        // Every edge sticker is its partner's partner.
+        for face in FaceType::enumerate() {
+            for (row, col) in [(0, 1), (1, 0), (1, 2), (2, 1)] {
+                let (f, r, c, _) = cube.edge_partner(face, row, col).unwrap();
+                assert_eq!(
+                    cube.edge_partner(f, r, c),
+                    Some((face, row, col, cube.face(face)[(row, col)]))
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,136 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::{
+    cube::{all_basic_moves, CornerType, Cube, EdgeType, Move},
+    group::{CyclicGroup, GroupAction, Unital},
+    permute::{CornerCubelet, CubePermutation3, EdgeCubelet},
+};
+
+/// A single edge or corner piece of a 3x3x3 cube.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Piece {
+    Edge(EdgeType),
+    Corner(CornerType),
+}
+
+/// Where a piece is, and how it is oriented.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum PieceState {
+    Edge(EdgeCubelet, CyclicGroup<2>),
+    Corner(CornerCubelet, CyclicGroup<3>),
+}
+
+impl PieceState {
+    fn of(permutation: &CubePermutation3, piece: Piece) -> Self {
+        match piece {
+            Piece::Edge(edge) => {
+                let (position, orientation) = permutation
+                    .edges()
+                    .act(&(EdgeCubelet(edge), CyclicGroup::identity()));
+                PieceState::Edge(position, orientation)
+            }
+            Piece::Corner(corner) => {
+                let (position, orientation) = permutation
+                    .corners()
+                    .act(&(CornerCubelet(corner), CyclicGroup::identity()));
+                PieceState::Corner(position, orientation)
+            }
+        }
+    }
+
+    fn perform(self, permutation: &CubePermutation3) -> Self {
+        match self {
+            PieceState::Edge(position, orientation) => {
+                let (position, orientation) = permutation.edges().act(&(position, orientation));
+                PieceState::Edge(position, orientation)
+            }
+            PieceState::Corner(position, orientation) => {
+                let (position, orientation) = permutation.corners().act(&(position, orientation));
+                PieceState::Corner(position, orientation)
+            }
+        }
+    }
+}
+
+/// The fewest outer layer moves (in the half turn metric) that bring the given piece home,
+/// correctly oriented, ignoring the rest of the cube.
+/// Returns None if the stickers do not form a valid set of pieces.
+pub fn moves_to_fix(cube: &Cube<3>, piece: Piece) -> Option<usize> {
+    moves_to_home_piece(cube, piece).map(|moves| moves.len())
+}
+
+/// A shortest sequence of outer layer moves (in the half turn metric) that brings the given piece home,
+/// correctly oriented, ignoring the rest of the cube. The search only tracks the one piece, so it is quick.
+/// Returns None if the stickers do not form a valid set of pieces.
+pub fn moves_to_home_piece(cube: &Cube<3>, piece: Piece) -> Option<Vec<Move>> {
+    let start = PieceState::of(&CubePermutation3::from_cube(cube)?, piece);
+    let target = PieceState::of(&CubePermutation3::identity(), piece);
+    let moves = all_basic_moves()
+        .into_iter()
+        .map(|mv| (mv, CubePermutation3::from_move(mv)))
+        .collect::<Vec<_>>();
+
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::new();
+    seen.insert(start);
+    queue.push_back((start, Vec::new()));
+    while let Some((state, path)) = queue.pop_front() {
+        if state == target {
+            return Some(path);
+        }
+        for (mv, permutation) in &moves {
+            let next = state.perform(permutation);
+            if seen.insert(next) {
+                let mut path = path.clone();
+                path.push(*mv);
+                queue.push_back((next, path));
+            }
+        }
+    }
+    // Every piece can be brought home, so the search always finds the target.
+    unreachable!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_to_fix_pieces() {
+        let cube = Cube::new().apply_algorithm("R").unwrap();
+        assert_eq!(moves_to_fix(&cube, Piece::Edge(EdgeType::UR)), Some(1));
+        assert_eq!(moves_to_fix(&cube, Piece::Corner(CornerType::FUR)), Some(1));
+        assert_eq!(moves_to_fix(&cube, Piece::Edge(EdgeType::UL)), Some(0));
+        assert_eq!(
+            moves_to_fix(&Cube::new(), Piece::Edge(EdgeType::UR)),
+            Some(0)
+        );
+
+        // There is no shorter way to bring the UF edge back than undoing all three moves.
+        let cube = Cube::new().apply_algorithm("F R U").unwrap();
+        assert_eq!(moves_to_fix(&cube, Piece::Edge(EdgeType::UF)), Some(3));
+    }
+
+    #[test]
+    fn moves_to_home() {
+        let cube = Cube::new().apply_algorithm("L D' F'").unwrap();
+        let edge = Piece::Edge(EdgeType::UF);
+        assert_eq!(
+            moves_to_home_piece(&cube, edge),
+            Some(vec!["F".parse().unwrap()])
+        );
+        assert_eq!(
+            moves_to_home_piece(&cube, Piece::Edge(EdgeType::UB)),
+            Some(Vec::new())
+        );
+        assert_eq!(moves_to_home_piece(&Cube::new(), edge), Some(Vec::new()));
+
+        let cube = Cube::new().apply_algorithm("F R U").unwrap();
+        for piece in [Piece::Edge(EdgeType::UF), Piece::Corner(CornerType::BDR)] {
+            let moves = moves_to_home_piece(&cube, piece).unwrap();
+            assert_eq!(Some(moves.len()), moves_to_fix(&cube, piece));
+            let fixed = cube.clone().apply_sequence(&moves);
+            assert_eq!(moves_to_fix(&fixed, piece), Some(0));
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use crate::{
+    cube::{Colour, Cube, Face, FaceType, FaceType::*, Move, MoveSequence},
+    group::Enumerable,
+};
+
+/// The side length of a sticker in an SVG net, in pixels.
+const STICKER_SIZE: usize = 20;
+
+impl Colour {
+    /// The colour used to draw stickers of this colour, as a hex string.
+    pub fn hex(self) -> &'static str {
+        match self {
+            Colour::Green => "#00a651",
+            Colour::Red => "#ed1c24",
+            Colour::White => "#ffffff",
+            Colour::Blue => "#0072bc",
+            Colour::Orange => "#f7941d",
+            Colour::Yellow => "#fff200",
+        }
+    }
+
+    /// The ANSI escape code that sets the text to this colour.
+    pub fn ansi(self) -> &'static str {
+        match self {
+            Colour::Green => "\x1b[92m",
+            Colour::Red => "\x1b[91m",
+            Colour::White => "\x1b[97m",
+            Colour::Blue => "\x1b[94m",
+            Colour::Orange => "\x1b[38;5;208m",
+            Colour::Yellow => "\x1b[93m",
+        }
+    }
+}
+
+/// A way of drawing a cube as text.
+pub trait Renderer {
+    fn render<const N: usize>(&self, cube: &Cube<N>) -> String;
+}
+
+/// Draws the cube as a net of colour letters. This is how cubes are displayed.
+pub struct AsciiRenderer;
+
+impl Renderer for AsciiRenderer {
+    fn render<const N: usize>(&self, cube: &Cube<N>) -> String {
+        cube.render_net(|face, i, j| cube.face(face)[(i, j)].letter().to_string())
+    }
+}
+
+/// Draws the cube as a net of colour letters, coloured for a terminal.
+pub struct AnsiRenderer;
+
+impl Renderer for AnsiRenderer {
+    fn render<const N: usize>(&self, cube: &Cube<N>) -> String {
+        // Colour the letters after laying out the net, so the escape codes don't count towards its alignment.
+        AsciiRenderer
+            .render(cube)
+            .chars()
+            .map(|c| {
+                match FaceType::enumerate()
+                    .map(FaceType::solved_colour)
+                    .into_iter()
+                    .find(|colour| colour.letter() == c)
+                {
+                    Some(colour) => format!("{}{}\x1b[0m", colour.ansi(), c),
+                    None => c.to_string(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Draws the cube as an SVG net.
+pub struct SvgRenderer;
+
+impl Renderer for SvgRenderer {
+    fn render<const N: usize>(&self, cube: &Cube<N>) -> String {
+        cube.to_svg()
+    }
+}
+
+impl<const N: usize> Cube<N> {
+    /// Draws the cube as an SVG net, laid out in the same way as the text net.
+    pub fn to_svg(&self) -> String {
+        svg_net(N, |face, i, j| self.face(face)[(i, j)].hex().to_string())
+    }
+}
+
+impl<const N: usize> Face<N> {
+    /// Draws the face as a table of coloured letters, with the row and column indices labelled.
+    /// This is useful for debugging code that reads or writes particular rows and columns.
+    pub fn debug_grid(&self) -> String {
+        let () = Self::HAS_LAYERS;
+        let width = (N - 1).to_string().len();
+        let mut result = " ".repeat(width);
+        for col in 0..N {
+            result += &format!(" {:>width$}", col, width = width);
+        }
+        for row in 0..N {
+            result += &format!("\n{:>width$}", row, width = width);
+            for col in 0..N {
+                let colour = self[(row, col)];
+                result += &format!(
+                    " {}{:>width$}\x1b[0m",
+                    colour.ansi(),
+                    colour.letter(),
+                    width = width
+                );
+            }
+        }
+        result
+    }
+}
+
+/// Draws an SVG net of an NxN cube, laid out in the same way as the text net,
+/// filling each sticker with the colour given by `fill`.
+pub(crate) fn svg_net(n: usize, fill: impl Fn(FaceType, usize, usize) -> String) -> String {
+    let mut result = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = 4 * n * STICKER_SIZE,
+        h = 3 * n * STICKER_SIZE,
+    );
+    // The position of each face on the net, in units of faces.
+    for (face, x, y) in [
+        (U, 1, 0),
+        (L, 0, 1),
+        (F, 1, 1),
+        (R, 2, 1),
+        (B, 3, 1),
+        (D, 1, 2),
+    ] {
+        for i in 0..n {
+            for j in 0..n {
+                result += &format!(
+                    r#"<rect x="{}" y="{}" width="{s}" height="{s}" fill="{}" stroke="black"/>"#,
+                    (x * n + j) * STICKER_SIZE,
+                    (y * n + i) * STICKER_SIZE,
+                    fill(face, i, j),
+                    s = STICKER_SIZE,
+                );
+            }
+        }
+    }
+    result += "</svg>";
+    result
+}
+
+/// Draws a printable sheet of scrambles for a 3x3x3 cube as an SVG.
+/// Each scramble gets a numbered row with its notation, and a net of the cube it produces,
+/// drawn at half size, so competitors can check that they scrambled correctly.
+pub fn scramble_sheet_svg(scrambles: &[Vec<Move>]) -> String {
+    // The size of each net, drawn at half size.
+    let (net_width, net_height) = (2 * 3 * STICKER_SIZE, 3 * 3 * STICKER_SIZE / 2);
+    let row_height = net_height + STICKER_SIZE;
+    let text_width = 40 * STICKER_SIZE;
+    let (width, height) = (text_width + net_width, scrambles.len() * row_height);
+    let mut result = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = width,
+        h = height,
+    );
+    for (index, moves) in scrambles.iter().enumerate() {
+        let top = index * row_height;
+        let cube = Cube::<3>::new().perform_all(moves.iter().copied());
+        let notation = MoveSequence {
+            moves: moves.clone(),
+        };
+        result += &format!(
+            r#"<g class="scramble"><text x="{x}" y="{y}" font-family="monospace" font-size="{s}">{}. {}</text><g transform="translate({}, {}) scale(0.5)">{}</g></g>"#,
+            index + 1,
+            notation,
+            text_width,
+            top + STICKER_SIZE / 2,
+            cube.to_svg(),
+            x = STICKER_SIZE / 2,
+            y = top + row_height / 2,
+            s = STICKER_SIZE,
+        );
+    }
+    result += "</svg>";
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn svg_net() {
+        let svg = Cube::<3>::new().to_svg();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>"));
+        assert_eq!(svg.matches("<rect").count(), 54);
+        assert_eq!(svg.matches(Colour::Yellow.hex()).count(), 9);
+    }
+
+    #[test]
+    fn scramble_sheet() {
+        let scrambles = ["R U2 F'", "L' D B2 R2 U'", "M' x"]
+            .map(|alg| alg.parse::<MoveSequence>().unwrap().moves);
+        let svg = scramble_sheet_svg(&scrambles);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>"));
+        assert_eq!(svg.matches(r#"<g class="scramble">"#).count(), 3);
+        assert_eq!(svg.matches("<rect").count(), 3 * 54);
+        for (number, alg) in ["1. R U2 F'", "2. L' D B2 R2 U'", "3. M' x"]
+            .into_iter()
+            .enumerate()
+        {
+            assert!(svg.contains(&format!(">{}</text>", alg)), "{}", number);
+        }
+        let first = Cube::<3>::new()
+            .apply_algorithm("R U2 F'")
+            .unwrap()
+            .to_svg();
+        assert!(svg.contains(&first));
+        assert_eq!(
+            scramble_sheet_svg(&[]),
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="920" height="0" viewBox="0 0 920 0"></svg>"#
+        );
+    }
+
+    #[test]
+    fn debug_grid() {
+        let mut cube = Cube::<3>::new();
+        cube.face_mut(U)[(0, 2)] = Colour::Red;
+        cube.face_mut(U)[(2, 1)] = Colour::Blue;
+        let plain = cube
+            .face(U)
+            .debug_grid()
+            .replace("\x1b[0m", "")
+            .replace(Colour::White.ansi(), "")
+            .replace(Colour::Red.ansi(), "")
+            .replace(Colour::Blue.ansi(), "");
+        assert_eq!(plain, "  0 1 2\n0 w w r\n1 w w w\n2 w b w");
+        assert_eq!(
+            cube.face(U).debug_grid().lines().nth(1).unwrap(),
+            format!(
+                "0 {w}w\x1b[0m {w}w\x1b[0m {r}r\x1b[0m",
+                w = Colour::White.ansi(),
+                r = Colour::Red.ansi()
+            )
+        );
+
+        let wide = Cube::<11>::new().face(F).debug_grid();
+        assert!(wide.starts_with("    0  1"));
+        assert!(wide.ends_with(&format!("{} g\x1b[0m", Colour::Green.ansi())));
+        assert!(wide.lines().last().unwrap().starts_with("10 "));
+    }
+
+    #[test]
+    fn renderers() {
+        let cube = Cube::<3>::new();
+
+        let ascii = AsciiRenderer.render(&cube);
+        assert_eq!(ascii, cube.to_string());
+        assert_eq!(ascii.lines().count(), 9);
+        assert_eq!(ascii.matches('g').count(), 9);
+
+        let ansi = AnsiRenderer.render(&cube);
+        assert_eq!(ansi.lines().count(), 9);
+        assert_eq!(ansi.matches("\x1b[0m").count(), 54);
+        assert_eq!(ansi.replace("\x1b[0m", "").matches('g').count(), 9);
+
+        let svg = SvgRenderer.render(&cube);
+        assert_eq!(svg, cube.to_svg());
+        assert_eq!(svg.matches("<rect").count(), 54);
+    }
+}
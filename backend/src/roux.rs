@@ -176,6 +176,13 @@ lazy_static::lazy_static! {
             "R U2' R' U2 R' F R F'",
             "L' U R U' L U R'",
             "R' U' R U' R' U R' F R F' U R",
+            // L
+            "F' U' F' U F R' F R",
+            "F U2 F' U2 R' F' R",
+            "F' U2 F R' F R F2 U2 F",
+            "F U2 F D F' U2 F D' F2",
+            "R' U2 R' D' R U2 R' D R2",
+            "R' U F' D F' U F D' F U' R",
             ]
             .into_iter()
             .map(|x| x.parse::<MoveSequence>().unwrap())
@@ -185,16 +192,7 @@ lazy_static::lazy_static! {
         let post_moves = vec!["U".parse::<MoveSequence>().unwrap()];
 
         AlgorithmicSolver::new("roux_cmll", alg_set, pre_moves, post_moves, |cube| {
-            [
-                cube.corners()
-                    .act(&(CornerCubelet(FUL), CyclicGroup::identity())),
-                cube.corners()
-                    .act(&(CornerCubelet(FUR), CyclicGroup::identity())),
-                cube.corners()
-                    .act(&(CornerCubelet(BUR), CyclicGroup::identity())),
-                cube.corners()
-                    .act(&(CornerCubelet(BUL), CyclicGroup::identity())),
-            ]
+            cmll_signature(cube)
         }, |seq| {
             seq.moves.len() as u64
         })
@@ -434,22 +432,27 @@ pub fn fourth_pair_action(permutation: CubePermutation3) -> Option<Action> {
         .map(|seq| move_sequence_to_intuitive_action("Fourth pair", seq.clone()))
 }
 
-pub fn cmll(permutation: CubePermutation3) -> Option<MoveSequence> {
-    let cmll = CMLL.solve(&[
-        permutation
-            .corners()
-            .act(&(CornerCubelet(FUL), CyclicGroup::identity())),
+fn cmll_signature(permutation: CubePermutation3) -> RouxCmllSignature {
+    [FUL, FUR, BUR, BUL].map(|corner| {
         permutation
             .corners()
-            .act(&(CornerCubelet(FUR), CyclicGroup::identity())),
-        permutation
-            .corners()
-            .act(&(CornerCubelet(BUR), CyclicGroup::identity())),
-        permutation
-            .corners()
-            .act(&(CornerCubelet(BUL), CyclicGroup::identity())),
-    ]);
-    cmll.map(|cmll| {
+            .act(&(CornerCubelet(corner), CyclicGroup::identity()))
+    })
+}
+
+pub fn cmll(permutation: CubePermutation3) -> Option<MoveSequence> {
+    let signature = cmll_signature(permutation);
+    // The algorithms only cover the cases that need one. When the corners are already solved up to an AUF,
+    // there is nothing to do, as the trailing AUF would be removed anyway.
+    let solved = cmll_signature(CubePermutation3::identity());
+    let skip = ["U", "U2", "U'"].into_iter().any(|auf| {
+        cmll_signature(CubePermutation3::from_move_sequence(auf.parse().unwrap()).op(permutation))
+            == solved
+    });
+    if signature == solved || skip {
+        return Some(MoveSequence { moves: Vec::new() });
+    }
+    CMLL.solve(&signature).map(|cmll| {
         let mut cmll = cmll.clone();
         // Remove any trailing AUF move.
         if let Some(Move { axis: Axis::UD, .. }) = cmll.moves.last() {
@@ -611,6 +614,7 @@ pub fn l4e_action(permutation: CubePermutation3) -> Option<Action> {
     l4e(permutation).map(|seq| move_sequence_to_intuitive_action("Last four edges", seq.clone()))
 }
 
+/// Solves the cube with the Roux method, returning None if it cannot be solved.
 pub fn solve(mut permutation: CubePermutation3) -> Option<Action> {
     let mut steps = Vec::new();
 
@@ -623,16 +627,21 @@ pub fn solve(mut permutation: CubePermutation3) -> Option<Action> {
         Some(())
     };
 
-    add_step(first_edge_action);
-    add_step(first_pair_action);
-    add_step(second_pair_action);
-    add_step(second_edge_action);
-    add_step(third_pair_action);
-    add_step(fourth_pair_action);
-    add_step(cmll_action);
-    add_step(eo_action);
-    add_step(lr_action);
-    add_step(l4e_action);
+    add_step(first_edge_action)?;
+    add_step(first_pair_action)?;
+    add_step(second_pair_action)?;
+    add_step(second_edge_action)?;
+    add_step(third_pair_action)?;
+    add_step(fourth_pair_action)?;
+    add_step(cmll_action)?;
+    add_step(eo_action)?;
+    add_step(lr_action)?;
+    add_step(l4e_action)?;
+
+    // Each step only looks at the pieces it solves, so an unsolvable cube can get through every step.
+    if permutation != CubePermutation3::identity() {
+        return None;
+    }
 
     Some(Action {
         reason: ActionReason::Solve,
@@ -644,7 +653,7 @@ pub fn solve(mut permutation: CubePermutation3) -> Option<Action> {
 #[cfg(test)]
 mod tests {
     use crate::{
-        group::{CyclicGroup, GroupAction, Magma},
+        group::{CyclicGroup, GroupAction, InverseSemigroup, Magma},
         permute::{CubePermutation3, EdgeCubelet},
     };
 
@@ -671,6 +680,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn cmll_l_cases() {
+        let solved = cmll_signature(CubePermutation3::identity());
+        let aufs =
+            ["U", "U2", "U'"].map(|auf| CubePermutation3::from_move_sequence(auf.parse().unwrap()));
+        for alg in [
+            "F' U' F' U F R' F R",
+            "F U2 F' U2 R' F' R",
+            "F' U2 F R' F R F2 U2 F",
+            "F U2 F D F' U2 F D' F2",
+            "R' U2 R' D' R U2 R' D R2",
+            "R' U F' D F' U F D' F U' R",
+        ] {
+            let case = CubePermutation3::from_move_sequence(alg.parse::<MoveSequence>().unwrap())
+                .inverse();
+            for permutation in std::iter::once(case).chain(aufs.map(|auf| auf.op(case))) {
+                let action = cmll_action(permutation).unwrap();
+                let after = CubePermutation3::from_move_sequence(action.steps.move_sequence())
+                    .op(permutation);
+                // The corners are solved up to an AUF, and the first two blocks are untouched.
+                assert!(
+                    cmll_signature(after) == solved
+                        || aufs
+                            .iter()
+                            .any(|auf| cmll_signature(auf.op(after)) == solved),
+                    "{}",
+                    alg
+                );
+                for corner in [FDL, FDR, BDL, BDR] {
+                    assert_eq!(
+                        after
+                            .corners()
+                            .act(&(CornerCubelet(corner), CyclicGroup::identity())),
+                        (CornerCubelet(corner), CyclicGroup::identity())
+                    );
+                }
+            }
+        }
+    }
+
     #[test]
     fn roux_two_blocks() {
         // Scramble the cube.
@@ -0,0 +1,128 @@
+use crate::{
+    cube::{all_basic_moves, CornerType, Cube, EdgeType, FaceType, Move, MoveSequence},
+    grade::redundancies,
+    group::Enumerable,
+    permute::{corner_facelets, edge_facelets, Facelet},
+};
+
+/// How many corners and edges are solved, that is, in their home position and correctly oriented.
+/// Centres are not counted, since outer layer moves never move them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolvedCount {
+    pub corners: usize,
+    pub edges: usize,
+}
+
+impl SolvedCount {
+    pub fn of(cube: &Cube<3>) -> Self {
+        Self {
+            corners: CornerType::enumerate()
+                .into_iter()
+                .filter(|&corner| facelets_solved(cube, &corner_facelets(corner)))
+                .count(),
+            edges: EdgeType::enumerate()
+                .into_iter()
+                .filter(|&edge| facelets_solved(cube, &edge_facelets(edge)))
+                .count(),
+        }
+    }
+
+    /// How many pieces would need to become solved or unsolved to reach the other count.
+    pub fn difference(&self, other: &SolvedCount) -> usize {
+        let diff = |a: usize, b: usize| (a as isize - b as isize).unsigned_abs();
+        diff(self.corners, other.corners) + diff(self.edges, other.edges)
+    }
+}
+
+fn facelets_solved(cube: &Cube<3>, facelets: &[Facelet]) -> bool {
+    facelets
+        .iter()
+        .all(|&(face, row, col)| cube.face(face)[(row, col)] == cube.face(face)[(1, 1)])
+}
+
+/// The number of stickers that do not match the centre of their face.
+pub fn sticker_distance(cube: &Cube<3>) -> usize {
+    FaceType::enumerate()
+        .into_iter()
+        .map(|face| {
+            let face = cube.face(face);
+            (0..3)
+                .flat_map(|row| (0..3).map(move |col| (row, col)))
+                .filter(|&idx| face[idx] != face[(1, 1)])
+                .count()
+        })
+        .sum()
+}
+
+/// Picks the outer layer move that brings the number of solved pieces closest to the target.
+/// Moves that would bring the cube closer to solved are avoided where possible,
+/// and ties are broken by taking the cube as far from solved as possible.
+/// Starting from a solved cube, repeatedly taking these steps produces a natural-looking scramble
+/// with roughly the given number of pieces left solved.
+pub fn reverse_step_toward(current: &Cube<3>, target_solved_pieces: &SolvedCount) -> Move {
+    let current_distance = sticker_distance(current);
+    let candidates = all_basic_moves()
+        .into_iter()
+        .map(|mv| {
+            let next = current.clone().perform(mv);
+            (
+                mv,
+                SolvedCount::of(&next).difference(target_solved_pieces),
+                sticker_distance(&next),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let any_moves_away = candidates
+        .iter()
+        .any(|&(_, _, distance)| distance >= current_distance);
+    candidates
+        .into_iter()
+        .filter(|&(_, _, distance)| !any_moves_away || distance >= current_distance)
+        .min_by_key(|&(_, difference, distance)| (difference, std::cmp::Reverse(distance)))
+        .unwrap()
+        .0
+}
+
+/// How many moves simplifying the scramble would remove, by cancelling and merging adjacent moves on the same axis.
+/// A good scramble has no redundancy.
+pub fn scramble_redundancy(moves: &[Move]) -> usize {
+    redundancies(moves)
+        .into_iter()
+        .map(|(_, run)| run.len() - MoveSequence { moves: run }.canonicalise().moves.len())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reverse_steps() {
+        let target = SolvedCount {
+            corners: 2,
+            edges: 4,
+        };
+        let mut cube = Cube::<3>::new();
+        let mut distance = sticker_distance(&cube);
+        let mut difference = SolvedCount::of(&cube).difference(&target);
+        for _ in 0..8 {
+            cube = cube.clone().perform(reverse_step_toward(&cube, &target));
+            let new_distance = sticker_distance(&cube);
+            assert!(new_distance >= distance);
+            distance = new_distance;
+            difference = difference.min(SolvedCount::of(&cube).difference(&target));
+        }
+        assert!(distance > 0);
+        assert!(difference < SolvedCount::of(&Cube::new()).difference(&target));
+    }
+
+    #[test]
+    fn redundancy() {
+        let redundancy =
+            |alg: &str| scramble_redundancy(&alg.parse::<MoveSequence>().unwrap().moves);
+        assert_eq!(redundancy("R U2 F' L D B2 R' U"), 0);
+        assert_eq!(redundancy("R U R R' F"), 2);
+        assert_eq!(redundancy("R L R2 U"), 1);
+    }
+}
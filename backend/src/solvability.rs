@@ -0,0 +1,379 @@
+use std::{collections::HashSet, fmt::Display};
+
+use crate::{
+    cube::{CornerType, Cube, EdgeType, FaceType, Move},
+    group::{CyclicGroup, Enumerable, GroupAction, Magma, Unital},
+    permute::{CornerCubelet, CubePermutation3, EdgeCubelet},
+};
+
+/// Explains whether a 3x3x3 cube can be solved, and if not, why not.
+/// A cube can be solved exactly when its stickers form a real set of pieces,
+/// and it satisfies the three invariants preserved by every move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolvabilityReport {
+    /// The number of stickers of each colour, indexed by colour. Each must be 9.
+    pub colour_counts: [usize; 6],
+    /// Whether the stickers form a real set of pieces, each appearing exactly once.
+    /// If not, the invariants below cannot be computed, and are left at their solvable values.
+    pub valid_pieces: bool,
+    /// Whether the permutation parities of the corners, edges and centres agree.
+    /// Swapping a single pair of pieces breaks this.
+    pub parity_matches: bool,
+    /// The sum of the corner orientations, modulo 3. Must be zero.
+    pub corner_twist: u8,
+    /// The sum of the edge orientations, modulo 2. Must be zero.
+    pub edge_flip: u8,
+}
+
+impl SolvabilityReport {
+    pub fn is_solvable(&self) -> bool {
+        self.colours_balanced()
+            && self.valid_pieces
+            && self.parity_matches
+            && self.corner_twist_ok()
+            && self.edge_flip_ok()
+    }
+
+    pub fn colours_balanced(&self) -> bool {
+        self.colour_counts.iter().all(|&count| count == 9)
+    }
+
+    pub fn corner_twist_ok(&self) -> bool {
+        self.corner_twist == 0
+    }
+
+    pub fn edge_flip_ok(&self) -> bool {
+        self.edge_flip == 0
+    }
+
+    /// Human-readable descriptions of each reason the cube cannot be solved.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        for colour in FaceType::enumerate().map(FaceType::solved_colour) {
+            let count = self.colour_counts[colour as usize];
+            if count != 9 {
+                problems.push(format!(
+                    "wrong number of {:?} stickers (found {}, must be 9)",
+                    colour, count
+                ));
+            }
+        }
+        if !self.valid_pieces {
+            problems.push("stickers do not form a valid set of pieces".to_string());
+        }
+        if !self.edge_flip_ok() {
+            problems.push(format!(
+                "single flipped edge (edge orientation sum = {}, must be 0)",
+                self.edge_flip
+            ));
+        }
+        if !self.corner_twist_ok() {
+            problems.push(format!(
+                "twisted corner (corner orientation sum = {}, must be 0)",
+                self.corner_twist
+            ));
+        }
+        if !self.parity_matches {
+            problems.push(
+                "two pieces swapped (corner and edge permutation parities differ)".to_string(),
+            );
+        }
+        problems
+    }
+}
+
+impl Display for SolvabilityReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_solvable() {
+            write!(f, "solvable")
+        } else {
+            write!(f, "{}", self.problems().join("; "))
+        }
+    }
+}
+
+impl Cube<3> {
+    /// Whether the cube can be solved: its stickers form a real set of pieces, no single corner is twisted,
+    /// no single edge is flipped, and no single pair of pieces is swapped.
+    /// `solvability_report` explains why not.
+    pub fn is_solvable(&self) -> bool {
+        self.solvability_report().is_solvable()
+    }
+
+    pub fn solvability_report(&self) -> SolvabilityReport {
+        let colour_counts = self.colour_counts();
+        match CubePermutation3::from_cube(self) {
+            Some(permutation) => SolvabilityReport {
+                colour_counts,
+                valid_pieces: true,
+                // Every quarter turn is an odd permutation on exactly two of the three kinds of piece.
+                parity_matches: !(permutation.corners().is_odd()
+                    ^ permutation.edges().is_odd()
+                    ^ permutation.centres().is_odd()),
+                corner_twist: permutation.corners().orientation_sum().get_value(),
+                edge_flip: permutation.edges().orientation_sum().get_value(),
+            },
+            None => SolvabilityReport {
+                colour_counts,
+                valid_pieces: false,
+                parity_matches: true,
+                corner_twist: 0,
+                edge_flip: 0,
+            },
+        }
+    }
+}
+
+/// Whether every edge is oriented, as it is after any sequence of moves without quarter turns of F or B.
+fn edges_oriented(permutation: &CubePermutation3) -> bool {
+    EdgeCubelet::enumerate().into_iter().all(|edge| {
+        permutation.edges().act(&(edge, CyclicGroup::identity())).1 == CyclicGroup::identity()
+    })
+}
+
+/// Whether every corner is oriented, as it is after any sequence of moves without quarter turns of F, B, R or L.
+fn corners_oriented(permutation: &CubePermutation3) -> bool {
+    CornerCubelet::enumerate().into_iter().all(|corner| {
+        permutation
+            .corners()
+            .act(&(corner, CyclicGroup::identity()))
+            .1
+            == CyclicGroup::identity()
+    })
+}
+
+/// The corners turned by R and U, which are the only corners that move in the <R, U> subgroup.
+const TWO_GEN_CORNERS: [CornerType; 6] = [
+    CornerType::FUR,
+    CornerType::FUL,
+    CornerType::FDR,
+    CornerType::BUR,
+    CornerType::BUL,
+    CornerType::BDR,
+];
+
+/// Where each of the corners turned by R and U has been moved, ignoring orientation.
+fn two_gen_corner_permutation(permutation: &CubePermutation3) -> [CornerCubelet; 6] {
+    TWO_GEN_CORNERS.map(|corner| {
+        permutation
+            .corners()
+            .act(&(CornerCubelet(corner), CyclicGroup::identity()))
+            .0
+    })
+}
+
+lazy_static::lazy_static! {
+    /// Only 120 of the 720 ways to permute these corners can be reached with R and U.
+    static ref TWO_GEN_CORNER_PERMUTATIONS: HashSet<[CornerCubelet; 6]> = {
+        let generators = ["R", "U"].map(|mv| CubePermutation3::from_move(mv.parse::<Move>().unwrap()));
+        let mut seen = HashSet::new();
+        let mut stack = vec![CubePermutation3::identity()];
+        seen.insert(two_gen_corner_permutation(&CubePermutation3::identity()));
+        while let Some(state) = stack.pop() {
+            for generator in generators {
+                let next = generator.op(state);
+                if seen.insert(two_gen_corner_permutation(&next)) {
+                    stack.push(next);
+                }
+            }
+        }
+        seen
+    };
+}
+
+/// Whether a 3x3x3 cube can be solved using only R and U moves.
+/// This holds exactly when the cube is solvable, every piece outside the R and U layers is solved,
+/// no edge is flipped, and the corners are in one of the permutations that R and U can reach.
+pub fn is_two_gen_solvable(cube: &Cube<3>) -> bool {
+    if !cube.is_solvable() {
+        return false;
+    }
+    let permutation = match CubePermutation3::from_cube(cube) {
+        Some(permutation) => permutation,
+        None => return false,
+    };
+    let solved_corner = |corner| {
+        let corner = (CornerCubelet(corner), CyclicGroup::identity());
+        permutation.corners().act(&corner) == corner
+    };
+    let solved_edge = |edge| {
+        let edge = (EdgeCubelet(edge), CyclicGroup::identity());
+        permutation.edges().act(&edge) == edge
+    };
+    permutation.centres() == CubePermutation3::identity().centres()
+        && [CornerType::FDL, CornerType::BDL]
+            .into_iter()
+            .all(solved_corner)
+        && [
+            EdgeType::DF,
+            EdgeType::DL,
+            EdgeType::DB,
+            EdgeType::FL,
+            EdgeType::BL,
+        ]
+        .into_iter()
+        .all(solved_edge)
+        && edges_oriented(&permutation)
+        && TWO_GEN_CORNER_PERMUTATIONS.contains(&two_gen_corner_permutation(&permutation))
+}
+
+/// The edges of the E slice, between the U and D layers.
+const E_SLICE_EDGES: [EdgeType; 4] = [EdgeType::FR, EdgeType::FL, EdgeType::BR, EdgeType::BL];
+
+/// Whether a 3x3x3 cube is in the subgroup G1 = <U, D, R2, L2, F2, B2>, the target of the first phase
+/// of Kociemba's two-phase algorithm.
+/// This holds exactly when the cube is solvable, the centres are in place, no edge is flipped,
+/// no corner is twisted, and the E slice edges are all in the E slice.
+pub fn is_in_g1(cube: &Cube<3>) -> bool {
+    if !cube.is_solvable() {
+        return false;
+    }
+    let permutation = match CubePermutation3::from_cube(cube) {
+        Some(permutation) => permutation,
+        None => return false,
+    };
+    permutation.centres() == CubePermutation3::identity().centres()
+        && edges_oriented(&permutation)
+        && corners_oriented(&permutation)
+        && E_SLICE_EDGES.into_iter().all(|edge| {
+            let (position, _) = permutation
+                .edges()
+                .act(&(EdgeCubelet(edge), CyclicGroup::identity()));
+            E_SLICE_EDGES.contains(&position.0)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cube::{Colour, CornerType, EdgeType, FaceType::*};
+    use crate::permute::{corner_facelets, edge_facelets};
+
+    use super::*;
+
+    fn scrambled() -> Cube<3> {
+        Cube::new()
+            .apply_algorithm("B R2 U2 F R' U' B2 F U R2 U2 L' D' R2 D L R' F' R F2 B2 U D' R L2")
+            .unwrap()
+    }
+
+    #[test]
+    fn two_gen() {
+        assert_eq!(TWO_GEN_CORNER_PERMUTATIONS.len(), 120);
+        assert!(is_two_gen_solvable(&Cube::new()));
+        let two_gen = |alg| is_two_gen_solvable(&Cube::new().apply_algorithm(alg).unwrap());
+        assert!(two_gen(
+            "R U2 R' U' R U' R' U2 R2 U R U R' U' R U2 R' U R2 U' R'"
+        ));
+        // Sune is two-gen, but no sequence of R and U moves swaps just two corners like the T-Perm.
+        assert!(two_gen("R U R' U R U2 R'"));
+        assert!(!two_gen("R U R' U' R' F R2 U' R' U' R U R' F'"));
+        assert!(!two_gen("R U F"));
+        assert!(!is_two_gen_solvable(&scrambled()));
+    }
+
+    #[test]
+    fn g1() {
+        assert!(is_in_g1(&Cube::new()));
+        let in_g1 = |alg| is_in_g1(&Cube::new().apply_algorithm(alg).unwrap());
+        assert!(in_g1("U R2 D' F2 L2 U2 B2 D R2 U' F2 L2 D2 B2 U R2"));
+        for alg in ["R", "F", "L' U2 L", "x", "R2 U R2 U' F"] {
+            assert!(!in_g1(alg), "{}", alg);
+        }
+        // Conjugating a U permutation by R moves an E slice edge into the U layer without flipping any edge
+        // or twisting any corner.
+        let cube = Cube::new()
+            .apply_algorithm("R R2 U R U R' U' R' U' R' U R' R'")
+            .unwrap();
+        let permutation = CubePermutation3::from_cube(&cube).unwrap();
+        assert!(corners_oriented(&permutation));
+        assert!(edges_oriented(&permutation));
+        assert!(!is_in_g1(&cube));
+        assert!(!is_in_g1(&scrambled()));
+    }
+
+    #[test]
+    fn solvable() {
+        assert!(Cube::new().is_solvable());
+        assert!(scrambled().is_solvable());
+        let report = scrambled().solvability_report();
+        assert!(report.is_solvable());
+        assert_eq!(report.colour_counts, [9; 6]);
+        assert_eq!(report.to_string(), "solvable");
+    }
+
+    #[test]
+    fn flipped_edge() {
+        let mut cube = scrambled();
+        let [(f1, r1, c1), (f2, r2, c2)] = edge_facelets(EdgeType::UF);
+        let colour = cube.face(f1)[(r1, c1)];
+        cube.face_mut(f1)[(r1, c1)] = cube.face(f2)[(r2, c2)];
+        cube.face_mut(f2)[(r2, c2)] = colour;
+
+        assert!(!cube.is_solvable());
+        let report = cube.solvability_report();
+        assert!(!report.is_solvable());
+        assert!(!report.edge_flip_ok());
+        assert!(report.corner_twist_ok() && report.parity_matches && report.colours_balanced());
+        assert_eq!(
+            report.to_string(),
+            "single flipped edge (edge orientation sum = 1, must be 0)"
+        );
+    }
+
+    #[test]
+    fn twisted_corner() {
+        let mut cube = scrambled();
+        let facelets = corner_facelets(CornerType::FUR);
+        let colours = facelets.map(|(face, row, col)| cube.face(face)[(row, col)]);
+        for (i, (face, row, col)) in facelets.into_iter().enumerate() {
+            cube.face_mut(face)[(row, col)] = colours[(i + 1) % 3];
+        }
+
+        assert!(!cube.is_solvable());
+        let report = cube.solvability_report();
+        assert_eq!(report.corner_twist, 2);
+        assert!(!report.corner_twist_ok());
+        assert!(report.edge_flip_ok() && report.parity_matches && report.colours_balanced());
+        assert_eq!(
+            report.to_string(),
+            "twisted corner (corner orientation sum = 2, must be 0)"
+        );
+    }
+
+    #[test]
+    fn swapped_edges() {
+        let mut cube = Cube::new();
+        for ((f1, r1, c1), (f2, r2, c2)) in edge_facelets(EdgeType::UF)
+            .into_iter()
+            .zip(edge_facelets(EdgeType::UR))
+        {
+            let colour = cube.face(f1)[(r1, c1)];
+            cube.face_mut(f1)[(r1, c1)] = cube.face(f2)[(r2, c2)];
+            cube.face_mut(f2)[(r2, c2)] = colour;
+        }
+
+        assert!(!cube.is_solvable());
+        let report = cube.solvability_report();
+        assert!(!report.parity_matches);
+        assert!(report.corner_twist_ok() && report.edge_flip_ok() && report.colours_balanced());
+        assert_eq!(
+            report.to_string(),
+            "two pieces swapped (corner and edge permutation parities differ)"
+        );
+    }
+
+    #[test]
+    fn invalid_pieces() {
+        let mut cube = Cube::new();
+        cube.face_mut(U)[(0, 0)] = cube.face(F)[(0, 0)];
+        let report = cube.solvability_report();
+        assert_eq!(report.colour_counts[Colour::Green as usize], 10);
+        assert_eq!(report.colour_counts[Colour::White as usize], 8);
+        assert_eq!(
+            report.to_string(),
+            "wrong number of Green stickers (found 10, must be 9); \
+            wrong number of White stickers (found 8, must be 9); \
+            stickers do not form a valid set of pieces"
+        );
+    }
+}
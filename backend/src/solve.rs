@@ -1,4 +1,12 @@
-use crate::{cube::MoveSequence, permute::CubePermutation3, Move, MoveSequenceConv};
+use crate::{
+    cube::{Cube, CubeError, FaceType, MoveSequence},
+    grade::{half_turn_metric, quarter_turn_metric},
+    group::Enumerable,
+    optimise::remove_rotations,
+    path::{shortest_path_within, MAX_DEPTH},
+    permute::CubePermutation3,
+    Move, MoveSequenceConv,
+};
 use wasm_bindgen::prelude::*;
 use web_sys::{Document, Element};
 
@@ -14,7 +22,7 @@ pub struct Action {
     pub steps: ActionSteps,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum ActionReason {
     /// This action was a full solve.
     Solve,
@@ -70,6 +78,268 @@ pub fn move_sequence_to_intuitive_action(step_name: &'static str, seq: MoveSeque
     }
 }
 
+/// A method that can be used to solve a cube.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SolveMethod {
+    Roux,
+}
+
+/// Options controlling how a solution is produced.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct SolveOptions {
+    /// Whether to return the moves exactly as each step of the method produced them.
+    /// Otherwise, moves that cancel or merge across the boundaries between steps are combined.
+    pub raw: bool,
+    /// Whether to solve the cube in whatever orientation it is held, so that the solution has no whole cube rotations.
+    /// Otherwise, if the centres are not in the standard orientation, the solution begins with the rotations
+    /// that bring them there, and the cube ends up solved in the standard orientation.
+    pub colour_neutral: bool,
+}
+
+impl SolveMethod {
+    /// Solves the cube with this method, returning None if it cannot be solved.
+    pub fn solve(self, cube: &Cube<3>) -> Option<MoveSequence> {
+        self.solve_with_options(cube, SolveOptions::default())
+    }
+
+    /// Solves the cube with this method and the given options, returning None if it cannot be solved.
+    /// The solution always solves the cube as it is held, whichever way round its centres are.
+    pub fn solve_with_options(self, cube: &Cube<3>, options: SolveOptions) -> Option<MoveSequence> {
+        let moves = self
+            .solve_in_steps(cube, options.raw)?
+            .into_iter()
+            .flat_map(|(_, moves)| moves)
+            .collect::<Vec<_>>();
+        if options.colour_neutral {
+            Some(MoveSequence {
+                moves: remove_rotations(&moves),
+            })
+        } else {
+            Some(MoveSequence { moves })
+        }
+    }
+
+    /// Solves the cube with this method, returning the moves of each step labelled with the step's name.
+    /// If the cube is not held in the standard orientation, the first step is the `Rotation` that brings it there.
+    /// Unless `raw` is set, moves that cancel or merge are combined,
+    /// and moves merged across the boundary between two steps are counted in the later step.
+    fn solve_in_steps(self, cube: &Cube<3>, raw: bool) -> Option<Vec<(&'static str, Vec<Move>)>> {
+        let rotation = standard_orientation(cube)?;
+        let rotated = cube.clone().apply_sequence(&rotation.moves);
+        let action = match self {
+            SolveMethod::Roux => crate::roux::solve(CubePermutation3::from_cube(&rotated)?)?,
+        };
+
+        let mut steps = Vec::new();
+        if !rotation.moves.is_empty() {
+            steps.push(("Rotation", rotation.moves));
+        }
+        let first_step = steps.len();
+        for (name, mut moves) in solve_steps(&action) {
+            if !raw {
+                moves = MoveSequence { moves }.canonicalise().moves;
+                // Bring moves on the same axis from the end of the earlier steps into this one, so they can be combined.
+                while let Some(previous) = steps[first_step..]
+                    .iter_mut()
+                    .rev()
+                    .map(|(_, moves)| moves)
+                    .find(|moves: &&mut Vec<Move>| !moves.is_empty())
+                {
+                    let axis = match (previous.last(), moves.first()) {
+                        (Some(last), Some(first)) if last.axis == first.axis => first.axis,
+                        _ => break,
+                    };
+                    let split = previous
+                        .iter()
+                        .rposition(|mv| mv.axis != axis)
+                        .map_or(0, |i| i + 1);
+                    moves.splice(0..0, previous.drain(split..));
+                    moves = MoveSequence { moves }.canonicalise().moves;
+                }
+            }
+            steps.push((name, moves));
+        }
+        Some(steps)
+    }
+}
+
+/// Move count statistics for a solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveReport {
+    /// The number of moves in the solution.
+    pub total: usize,
+    /// The length of the solution in the half turn metric.
+    pub htm: usize,
+    /// The length of the solution in the quarter turn metric.
+    pub qtm: usize,
+    /// The name of each step of the solve method, and how many moves it took.
+    pub steps: Vec<(&'static str, usize)>,
+}
+
+/// The name of each step of a solve, and the moves it took.
+fn solve_steps(action: &Action) -> Vec<(&'static str, Vec<Move>)> {
+    match &action.steps {
+        ActionSteps::Sequence { actions } => actions
+            .iter()
+            .map(|step| {
+                let name = match step.reason {
+                    ActionReason::SolveStep { step_name } => step_name,
+                    _ => "",
+                };
+                (name, step.steps.move_sequence().moves)
+            })
+            .collect(),
+        ActionSteps::Move { mv } => vec![("", vec![*mv])],
+    }
+}
+
+/// Solves the cube with the Roux method, returning the solution and how many moves each step took.
+/// The solution is the one `SolveMethod::solve` gives, so the step counts add up to its length.
+/// Returns None if the cube cannot be solved.
+pub fn solve_with_report(cube: Cube<3>) -> Option<(Vec<Move>, SolveReport)> {
+    let steps = SolveMethod::Roux.solve_in_steps(&cube, false)?;
+    let moves = steps
+        .iter()
+        .flat_map(|(_, moves)| moves.iter().copied())
+        .collect::<Vec<_>>();
+    let report = SolveReport {
+        total: moves.len(),
+        htm: half_turn_metric(&moves),
+        qtm: quarter_turn_metric(&moves),
+        steps: steps
+            .into_iter()
+            .map(|(name, moves)| (name, moves.len()))
+            .collect(),
+    };
+    Some((moves, report))
+}
+
+/// Solves the cube with the Roux method, returning the moves of each step labelled with the step's name,
+/// ready to be shown with `format_phased`.
+/// The phases together make up the solution that `SolveMethod::solve` gives.
+/// Returns None if the cube cannot be solved.
+pub fn solve_phased(cube: &Cube<3>) -> Option<Vec<(String, Vec<Move>)>> {
+    Some(
+        SolveMethod::Roux
+            .solve_in_steps(cube, false)?
+            .into_iter()
+            .map(|(name, moves)| (name.to_string(), moves))
+            .collect(),
+    )
+}
+
+/// Writes a solution split into phases, each labelled and with its move count,
+/// like `Phase 1 (2): R U | Phase 2 (1): F'`.
+pub fn format_phased(phases: &[(String, Vec<Move>)]) -> String {
+    phases
+        .iter()
+        .map(|(label, moves)| {
+            let mut phase = format!("{} ({})", label, moves.len());
+            if !moves.is_empty() {
+                phase += ": ";
+                phase += &MoveSequence {
+                    moves: moves.clone(),
+                }
+                .to_string();
+            }
+            phase
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// Solves the mirror image of the cube with the Roux method, and mirrors the solution back.
+/// The result still solves the cube, but favours the left hand wherever the usual solution favours the right.
+/// Returns None if the cube cannot be solved.
+pub fn solve_mirrored(cube: Cube<3>) -> Option<Vec<Move>> {
+    let solution = SolveMethod::Roux.solve(&cube.mirrored())?;
+    Some(
+        solution
+            .moves
+            .into_iter()
+            .map(|mv| mv.mirrored(3))
+            .collect(),
+    )
+}
+
+/// Solves the cube quickly with the Roux method, but if that takes more than `target_len` moves,
+/// searches for a solution of at most `target_len` moves instead.
+/// There is no Kociemba solver to fall back on, so the fallback is an optimal search, which is only
+/// worth running when `target_len` is at most `path::MAX_DEPTH`. Otherwise, or if the search finds nothing,
+/// the Roux solution is returned even though it is longer than `target_len`.
+/// Panics if the cube cannot be solved.
+pub fn solve_bounded(cube: Cube<3>, target_len: usize) -> Vec<Move> {
+    let fast = SolveMethod::Roux
+        .solve(&cube)
+        .expect("cube cannot be solved")
+        .moves;
+    if fast.len() <= target_len || target_len > MAX_DEPTH {
+        return fast;
+    }
+    shortest_path_within(&cube, &Cube::new(), target_len).unwrap_or(fast)
+}
+
+/// Draws an SVG frame of the cube before the solution and after each of its moves,
+/// ready to be assembled into an animation.
+/// If no solution is found, there is only the first frame.
+pub fn solve_frames(cube: &Cube<3>, method: SolveMethod) -> Vec<String> {
+    let solution = method
+        .solve(cube)
+        .unwrap_or(MoveSequence { moves: Vec::new() });
+    cube.trace(&solution.moves)
+        .iter()
+        .map(Cube::to_svg)
+        .collect()
+}
+
+/// Finds the whole cube rotation that puts each centre on its own face, if there is one.
+fn standard_orientation(cube: &Cube<3>) -> Option<MoveSequence> {
+    let centres_standard = |cube: &Cube<3>| {
+        FaceType::enumerate()
+            .into_iter()
+            .all(|face| cube.face(face)[(1, 1)] == face.solved_colour())
+    };
+    // First bring the right centre to the top, then turn it about the vertical axis.
+    for first in ["", "x", "x2", "x'", "z", "z'"] {
+        for second in ["", "y", "y2", "y'"] {
+            let rotation = [first, second]
+                .into_iter()
+                .filter(|s| !s.is_empty())
+                .flat_map(|s| s.parse::<MoveSequence>().unwrap().moves)
+                .collect::<Vec<_>>();
+            let rotated = rotation
+                .iter()
+                .fold(cube.clone(), |cube, &mv| cube.perform(mv));
+            if centres_standard(&rotated) {
+                return Some(MoveSequence { moves: rotation });
+            }
+        }
+    }
+    None
+}
+
+/// Solves a cube given as a facelet string, for example from a scanner.
+/// If the cube is not held in the standard orientation, the solution begins with the rotations needed to bring it there.
+/// The cube is checked to be solvable first, and if not, the error explains why.
+pub fn solve_from_facelets(facelets: &str) -> Result<String, CubeError> {
+    let cube = Cube::<3>::from_facelets(facelets)?;
+    cube.try_validate()?;
+    let report = cube.solvability_report();
+    if !report.is_solvable() {
+        return Err(CubeError::Unsolvable(report));
+    }
+
+    // The solver brings the cube into the standard orientation itself, so the centres are only checked on failure.
+    let solution = SolveMethod::Roux.solve(&cube).ok_or_else(|| {
+        if standard_orientation(&cube).is_none() {
+            CubeError::InvalidCentres
+        } else {
+            CubeError::NoSolution
+        }
+    })?;
+    Ok(solution.to_string())
+}
+
 #[wasm_bindgen]
 #[allow(dead_code)]
 pub fn action_to_div() -> MoveSequenceConv {
@@ -195,3 +465,263 @@ fn add_action_to_div(action: Action, document: &Document, div: &Element) -> Resu
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{cube::Colour, ergonomics::face_usage};
+
+    use super::*;
+
+    /// The scramble "U2 B D' B U2 L F' D B' U2 D R' U2 B R2 D' B' D2 L B2 F2 U D2 F B2".
+    const SCRAMBLED: &str = "BRURUUDLULRFRRLLDBBUBDFDUBDFUFUDBFLRRBLBLFDFLRFUFBLDDR";
+
+    fn check_solution(facelets: &str) {
+        let solution = solve_from_facelets(facelets)
+            .unwrap()
+            .parse::<MoveSequence>()
+            .unwrap();
+        let solved = solution
+            .moves
+            .into_iter()
+            .fold(Cube::<3>::from_facelets(facelets).unwrap(), |cube, mv| {
+                cube.perform(mv)
+            });
+        assert_eq!(solved, Cube::new());
+    }
+
+    #[test]
+    fn solve_facelets() {
+        check_solution(SCRAMBLED);
+        // The same scramble, held after a z rotation.
+        check_solution("DBRFLBLFLDRBLURUUUUDBBFUDDBLRLDRRBLFFUFLDURBFULRFBDRFD");
+    }
+
+    #[test]
+    fn frames() {
+        let cube = Cube::<3>::from_facelets(SCRAMBLED).unwrap();
+        let solution = SolveMethod::Roux.solve(&cube).unwrap();
+        let frames = solve_frames(&cube, SolveMethod::Roux);
+        assert_eq!(frames.len(), solution.moves.len() + 1);
+        assert_eq!(frames[0], cube.to_svg());
+        assert_eq!(frames.last(), Some(&Cube::<3>::new().to_svg()));
+    }
+
+    #[test]
+    fn report() {
+        let cube = Cube::<3>::from_facelets(SCRAMBLED).unwrap();
+        let (moves, report) = solve_with_report(cube.clone()).unwrap();
+        assert_eq!(report.total, moves.len());
+        assert_eq!(
+            report.steps.iter().map(|&(_, count)| count).sum::<usize>(),
+            moves.len()
+        );
+        assert_eq!(report.steps[0].0, "First edge");
+        assert!(report.qtm >= report.htm);
+        let solved = moves.into_iter().fold(cube, |cube, mv| cube.perform(mv));
+        assert_eq!(solved, Cube::new());
+
+        // A flipped edge cannot be solved, so there is nothing to report.
+        let flipped = Cube::<3>::solved_with_edits_unchecked(&[
+            (FaceType::U, 2, 1, Colour::Green),
+            (FaceType::F, 0, 1, Colour::White),
+        ]);
+        assert_eq!(solve_with_report(flipped), None);
+
+        // A cube held upside down is turned the right way up first, and moves that cancel are combined.
+        let upside_down = Cube::<3>::from_facelets(SCRAMBLED)
+            .unwrap()
+            .apply_algorithm("x2")
+            .unwrap();
+        let (moves, report) = solve_with_report(upside_down.clone()).unwrap();
+        assert_eq!(moves, SolveMethod::Roux.solve(&upside_down).unwrap().moves);
+        assert_eq!(report.steps[0], ("Rotation", 1));
+        assert_eq!(report.total, moves.len());
+        let raw = SolveOptions {
+            raw: true,
+            ..SolveOptions::default()
+        };
+        assert!(
+            report.total
+                <= SolveMethod::Roux
+                    .solve_with_options(&upside_down, raw)
+                    .unwrap()
+                    .moves
+                    .len()
+        );
+        assert_eq!(upside_down.apply_sequence(&moves), Cube::new());
+    }
+
+    #[test]
+    fn mirrored() {
+        let cube = Cube::<3>::from_facelets(SCRAMBLED).unwrap();
+        let mirrored = solve_mirrored(cube.clone()).unwrap();
+        assert_eq!(cube.clone().perform_all(mirrored.clone()), Cube::new());
+        let usual = SolveMethod::Roux.solve(&cube).unwrap().moves;
+        assert_ne!(face_usage(&mirrored), face_usage(&usual));
+        // Roux favours R over L, so the mirrored solution turns L more often.
+        let left = |moves: &[Move]| face_usage(moves).get(&FaceType::L).copied();
+        assert!(left(&mirrored) > left(&usual));
+
+        // The mirror image of a cube with a twisted corner still has a twisted corner.
+        let twisted = Cube::<3>::solved_with_edits_unchecked(&[
+            (FaceType::U, 2, 2, Colour::Green),
+            (FaceType::F, 0, 2, Colour::Red),
+            (FaceType::R, 0, 0, Colour::White),
+        ]);
+        assert_eq!(solve_mirrored(twisted), None);
+    }
+
+    #[test]
+    fn phased() {
+        let moves = |alg: &str| alg.parse::<MoveSequence>().unwrap().moves;
+        let phases = [
+            ("Phase 1".to_string(), moves("R U")),
+            ("Phase 2".to_string(), Vec::new()),
+            ("Phase 3".to_string(), moves("F' D2 M")),
+        ];
+        assert_eq!(
+            format_phased(&phases),
+            "Phase 1 (2): R U | Phase 2 (0) | Phase 3 (3): F' D2 M"
+        );
+
+        let cube = Cube::<3>::from_facelets(SCRAMBLED).unwrap();
+        let phases = solve_phased(&cube).unwrap();
+        let (_, report) = solve_with_report(cube.clone()).unwrap();
+        assert_eq!(
+            phases
+                .iter()
+                .map(|(label, moves)| (label.as_str(), moves.len()))
+                .collect::<Vec<_>>(),
+            report.steps
+        );
+        let formatted = format_phased(&phases);
+        assert!(formatted.starts_with(&format!("First edge ({})", phases[0].1.len())));
+        assert_eq!(formatted.matches(" | ").count(), phases.len() - 1);
+        let solved = cube.perform_all(phases.into_iter().flat_map(|(_, moves)| moves));
+        assert_eq!(solved, Cube::new());
+    }
+
+    #[test]
+    fn bounded() {
+        let cube = Cube::<3>::new().apply_algorithm("R U F' L2 D B").unwrap();
+        let fast = SolveMethod::Roux.solve(&cube).unwrap().moves;
+        assert!(fast.len() > 6);
+        let bounded = solve_bounded(cube.clone(), 6);
+        assert_eq!(bounded.len(), 6);
+        assert_eq!(cube.clone().perform_all(bounded), Cube::new());
+        assert_eq!(solve_bounded(cube.clone(), fast.len()), fast);
+        // There is no solution this short, so the search gives up straight away.
+        assert_eq!(solve_bounded(cube, 3), fast);
+
+        // The target is out of reach of the search, so it is not run at all.
+        let cube = Cube::<3>::from_facelets(SCRAMBLED).unwrap();
+        let fast = SolveMethod::Roux.solve(&cube).unwrap().moves;
+        assert!(fast.len() > MAX_DEPTH + 1);
+        let bounded = solve_bounded(cube.clone(), MAX_DEPTH + 1);
+        assert_eq!(bounded, fast);
+        assert_eq!(cube.perform_all(bounded), Cube::new());
+    }
+
+    #[test]
+    fn cancellation() {
+        let cube = Cube::<3>::from_facelets(SCRAMBLED).unwrap();
+        let raw = SolveMethod::Roux
+            .solve_with_options(
+                &cube,
+                SolveOptions {
+                    raw: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let cancelled = SolveMethod::Roux.solve(&cube).unwrap();
+        assert!(cancelled.moves.len() <= raw.moves.len());
+        for solution in [raw, cancelled] {
+            let solved = solution
+                .moves
+                .into_iter()
+                .fold(cube.clone(), |cube, mv| cube.perform(mv));
+            assert_eq!(solved, Cube::new());
+        }
+    }
+
+    #[test]
+    fn non_standard_orientation() {
+        // Hold the cube with yellow on top.
+        let cube = ["x2", "y"]
+            .into_iter()
+            .map(|mv| mv.parse().unwrap())
+            .fold(Cube::<3>::from_facelets(SCRAMBLED).unwrap(), Cube::perform);
+        assert_eq!(cube.face(FaceType::U)[(1, 1)], Colour::Yellow);
+        let perform = |solution: MoveSequence| {
+            solution
+                .moves
+                .into_iter()
+                .fold(cube.clone(), |cube, mv| cube.perform(mv))
+        };
+
+        let solution = SolveMethod::Roux.solve(&cube).unwrap();
+        assert_eq!(
+            solution.moves[..2],
+            "x2 y".parse::<MoveSequence>().unwrap().moves
+        );
+        assert_eq!(perform(solution), Cube::new());
+
+        let solution = SolveMethod::Roux
+            .solve_with_options(
+                &cube,
+                SolveOptions {
+                    colour_neutral: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert!(solution
+            .moves
+            .iter()
+            .all(|mv| (mv.start_depth, mv.end_depth) != (0, 3)));
+        let solved = perform(solution);
+        assert_eq!(solved.face(FaceType::U)[(1, 1)], Colour::Yellow);
+        assert!(FaceType::enumerate()
+            .into_iter()
+            .all(|face| solved.is_face_solved(face)));
+    }
+
+    #[test]
+    fn unsolvable() {
+        // Each cube is made of real pieces, but has a flipped edge, a twisted corner, or two swapped edges.
+        let solved = Cube::<3>::from_facelets(SCRAMBLED).unwrap();
+        for swap in [(5, 10), (8, 20), (1, 46)] {
+            let mut facelets = SCRAMBLED.chars().collect::<Vec<_>>();
+            facelets.swap(swap.0, swap.1);
+            let cube = Cube::<3>::from_facelets(&facelets.into_iter().collect::<String>()).unwrap();
+            assert_ne!(cube, solved);
+            assert_eq!(SolveMethod::Roux.solve(&cube), None, "{:?}", swap);
+        }
+        assert!(SolveMethod::Roux.solve(&solved).is_some());
+        assert_eq!(
+            SolveMethod::Roux.solve(&Cube::new()),
+            Some(MoveSequence { moves: Vec::new() })
+        );
+    }
+
+    #[test]
+    fn unsolvable_facelets() {
+        // Flip the UR edge, whose stickers are the sixth U sticker and the second R sticker.
+        let mut facelets = SCRAMBLED.chars().collect::<Vec<_>>();
+        facelets.swap(5, 10);
+        let facelets = facelets.into_iter().collect::<String>();
+        let error = solve_from_facelets(&facelets).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "the cube cannot be solved: single flipped edge (edge orientation sum = 1, must be 0)"
+        );
+
+        assert_eq!(
+            solve_from_facelets(&SCRAMBLED[1..])
+                .unwrap_err()
+                .to_string(),
+            "expected 54 facelets but found 53"
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{
+    cube::{facelet_coords, facelet_index, Cube, FaceType, Move},
+    render::svg_net,
+};
+
+/// A cube whose stickers may be given custom colours on top of the usual six, as on a picture cube.
+/// The custom colours only change how the cube looks: the underlying cube is still coloured with the
+/// usual six colours, and is what the solvers work with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StickeredCube<const N: usize> {
+    cube: Cube<N>,
+    /// The custom colour of each sticker, if it has one, indexed as in a facelet string.
+    overlay: Vec<Option<u8>>,
+}
+
+impl<const N: usize> StickeredCube<N> {
+    /// A cube with no custom colours.
+    pub fn new(cube: Cube<N>) -> Self {
+        Self {
+            cube,
+            overlay: vec![None; 6 * N * N],
+        }
+    }
+
+    /// The cube in the usual six colours.
+    pub fn cube(&self) -> &Cube<N> {
+        &self.cube
+    }
+
+    pub fn extended_colour(&self, face: FaceType, row: usize, col: usize) -> Option<u8> {
+        self.overlay[facelet_index(face, row, col, N)]
+    }
+
+    pub fn set_extended_colour(
+        &mut self,
+        face: FaceType,
+        row: usize,
+        col: usize,
+        colour: Option<u8>,
+    ) {
+        self.overlay[facelet_index(face, row, col, N)] = colour;
+    }
+
+    /// Performs a move, carrying each custom colour along with the sticker it is on.
+    pub fn perform(self, mv: Move) -> Self {
+        let overlay = mv
+            .permutation_array(N)
+            .into_iter()
+            .map(|from| self.overlay[from])
+            .collect();
+        Self {
+            cube: self.cube.perform(mv),
+            overlay,
+        }
+    }
+
+    /// Draws the cube as an SVG net, drawing stickers with custom colours in those colours.
+    pub fn to_svg(&self) -> String {
+        svg_net(N, |face, i, j| match self.extended_colour(face, i, j) {
+            Some(colour) => extended_colour_fill(colour),
+            None => self.cube.face(face)[(i, j)].hex().to_string(),
+        })
+    }
+
+    /// The positions of the stickers with custom colours, and their colours.
+    pub fn extended_stickers(&self) -> Vec<(FaceType, usize, usize, u8)> {
+        self.overlay
+            .iter()
+            .enumerate()
+            .filter_map(|(index, colour)| {
+                let (face, row, col) = facelet_coords(index, N);
+                colour.map(|colour| (face, row, col, colour))
+            })
+            .collect()
+    }
+}
+
+/// The colour used to draw a sticker with a custom colour.
+/// Custom colours are spread evenly around the colour wheel.
+fn extended_colour_fill(colour: u8) -> String {
+    format!("hsl({}, 60%, 40%)", colour as usize * 360 / 256)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cube::{Colour, MoveSequence};
+
+    use super::*;
+
+    #[test]
+    fn overlay_follows_moves() {
+        let mut cube = StickeredCube::<3>::new(Cube::new());
+        cube.set_extended_colour(FaceType::U, 2, 2, Some(17));
+
+        let cube = cube.perform("R".parse().unwrap());
+        assert_eq!(cube.extended_stickers(), vec![(FaceType::B, 0, 0, 17)]);
+        assert_eq!(cube.cube().face(FaceType::B)[(0, 0)], Colour::White);
+
+        let sexy = "R U R' U'".parse::<MoveSequence>().unwrap().moves;
+        let cube = (0..6)
+            .flat_map(|_| sexy.iter().copied())
+            .fold(cube, StickeredCube::perform);
+        assert_eq!(cube.extended_stickers(), vec![(FaceType::B, 0, 0, 17)]);
+        assert_eq!(cube.cube(), &Cube::new().perform("R".parse().unwrap()));
+
+        let svg = cube.to_svg();
+        assert_eq!(svg.matches("<rect").count(), 54);
+        assert_eq!(svg.matches(&extended_colour_fill(17)).count(), 1);
+        assert_eq!(svg.matches(Colour::White.hex()).count(), 8);
+    }
+}
@@ -0,0 +1,62 @@
+use crate::cube::Move;
+
+/// A move made during a timed solve, with the time it was made in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimedMove {
+    pub mv: Move,
+    pub at_ms: f64,
+}
+
+/// The number of moves made per second, from the first move to the last.
+/// Returns zero if there are not enough moves to measure a duration.
+pub fn tps(log: &[TimedMove]) -> f64 {
+    match (log.first(), log.last()) {
+        (Some(first), Some(last)) if last.at_ms > first.at_ms => {
+            log.len() as f64 / ((last.at_ms - first.at_ms) / 1000.0)
+        }
+        _ => 0.0,
+    }
+}
+
+/// The longest time between two consecutive moves, in milliseconds.
+/// Long pauses usually show where the solver had to stop and look for the next step.
+pub fn longest_pause_ms(log: &[TimedMove]) -> f64 {
+    log.windows(2)
+        .map(|pair| pair[1].at_ms - pair[0].at_ms)
+        .fold(0.0, f64::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cube::MoveSequence;
+
+    use super::*;
+
+    fn timed(alg: &str, times: &[f64]) -> Vec<TimedMove> {
+        alg.parse::<MoveSequence>()
+            .unwrap()
+            .moves
+            .into_iter()
+            .zip(times)
+            .map(|(mv, &at_ms)| TimedMove { mv, at_ms })
+            .collect()
+    }
+
+    #[test]
+    fn analytics() {
+        let log = timed(
+            "R U R' U' R' F R2 U' R'",
+            &[
+                0.0, 100.0, 200.0, 300.0, 1500.0, 1600.0, 1700.0, 1850.0, 2000.0,
+            ],
+        );
+        assert_eq!(tps(&log), 4.5);
+        assert_eq!(longest_pause_ms(&log), 1200.0);
+
+        assert_eq!(tps(&[]), 0.0);
+        assert_eq!(longest_pause_ms(&[]), 0.0);
+        let single = timed("R", &[250.0]);
+        assert_eq!(tps(&single), 0.0);
+        assert_eq!(longest_pause_ms(&single), 0.0);
+    }
+}
@@ -3,6 +3,7 @@
 #![cfg(target_arch = "wasm32")]
 
 extern crate wasm_bindgen_test;
+use autocuber::{cubes_equal, init};
 use wasm_bindgen_test::*;
 
 wasm_bindgen_test_configure!(run_in_browser);
@@ -11,3 +12,116 @@ wasm_bindgen_test_configure!(run_in_browser);
 fn pass() {
     assert_eq!(1 + 1, 2);
 }
+
+#[wasm_bindgen_test]
+fn apply_many() {
+    let mut universe = init();
+    let solved = universe.net();
+
+    let sexy = "R U R' U'".to_string();
+    universe.apply_many(vec![sexy.clone(); 6]).unwrap();
+    assert_eq!(universe.net(), solved);
+
+    universe.apply_many(vec![sexy.clone()]).unwrap();
+    let scrambled = universe.net();
+    assert_ne!(scrambled, solved);
+
+    let error = universe
+        .apply_many(vec![sexy, "R Q R'".to_string()])
+        .unwrap_err();
+    assert_eq!(
+        error.as_string().unwrap(),
+        "algorithm 1 is invalid: invalid move 'Q' at position 2: unknown face 'Q'"
+    );
+    // Nothing was applied, not even the valid first algorithm.
+    assert_eq!(universe.net(), scrambled);
+
+    // A layer deeper than the cube is an error, not a panic part way through the list.
+    let error = universe
+        .apply_many(vec!["R".to_string(), "U 5R".to_string()])
+        .unwrap_err();
+    assert_eq!(
+        error.as_string().unwrap(),
+        "algorithm 1 is invalid: invalid move '5R' at position 2: cannot turn layers 4 to 5 of a cube of size 3"
+    );
+    assert_eq!(universe.net(), scrambled);
+}
+
+#[wasm_bindgen_test]
+fn compare_facelets() {
+    let solved = "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB";
+    let r = "UUFUUFUUFRRRRRRRRRFFDFFDFFDDDBDDBDDBLLLLLLLLLUBBUBBUBB";
+    assert_eq!(cubes_equal(solved, solved), Ok(true));
+    assert_eq!(cubes_equal(solved, r), Ok(false));
+    assert_eq!(
+        cubes_equal(solved, "UUU").unwrap_err().as_string().unwrap(),
+        "expected 54 facelets but found 3"
+    );
+}
+
+#[wasm_bindgen_test]
+fn perform_timed() {
+    let mut universe = init();
+    universe.perform_timed("R", 0.0).unwrap();
+    universe.perform_timed("U", 500.0).unwrap();
+    let net = universe.net();
+
+    let error = universe.perform_timed("5R", 1000.0).unwrap_err();
+    assert_eq!(
+        error.as_string().unwrap(),
+        "cannot apply 5R: cannot turn layers 4 to 5 of a cube of size 3"
+    );
+    // The invalid move is neither performed nor logged.
+    assert_eq!(universe.net(), net);
+    assert_eq!(universe.tps(), 4.0);
+    assert_eq!(universe.longest_pause_ms(), 500.0);
+}
+
+#[wasm_bindgen_test]
+fn perform_cancelling() {
+    let mut universe = init();
+    let solved = universe.net();
+
+    universe.perform_cancelling("U").unwrap();
+    let before = universe.net();
+    universe.perform_cancelling("R").unwrap();
+    assert_eq!(universe.history(), "U R");
+    universe.perform_cancelling("R'").unwrap();
+    assert_eq!(universe.history(), "U");
+    assert_eq!(universe.net(), before);
+    universe.perform_cancelling("U'").unwrap();
+    assert_eq!(universe.history(), "");
+    assert_eq!(universe.net(), solved);
+
+    universe.set_cancelling(false);
+    universe.perform_cancelling("R").unwrap();
+    universe.perform_cancelling("R'").unwrap();
+    assert_eq!(universe.history(), "R R'");
+    assert_eq!(universe.net(), solved);
+
+    for token in ["5R", "5L"] {
+        let error = universe.perform_cancelling(token).unwrap_err();
+        assert_eq!(
+            error.as_string().unwrap(),
+            format!(
+                "cannot apply {}: cannot turn layers 4 to 5 of a cube of size 3",
+                token
+            )
+        );
+    }
+    assert_eq!(universe.history(), "R R'");
+    assert_eq!(universe.net(), solved);
+}
+
+#[wasm_bindgen_test]
+fn scramble_to_current() {
+    let mut universe = init();
+    universe
+        .apply_many(vec!["R U2 F' L D B2".to_string(), "M' y S E2".to_string()])
+        .unwrap();
+    let scramble = universe.scramble_to_current().unwrap();
+
+    let mut fresh = init();
+    fresh.apply_many(vec![scramble]).unwrap();
+    assert_eq!(fresh.net(), universe.net());
+}